@@ -0,0 +1,111 @@
+// one group per day timing parsing and both parts on the sample and the real input,
+// measured by `bench [part]` command of the day binaries
+//
+// cargo bench                 quick run without the heavy parts
+// cargo bench -- --heavy      all the parts
+// cargo bench -- d05 d12      only the given days
+use std::process::Command;
+
+const DAYS: [(&str, &str); 25] = [
+    ("d01", env!("CARGO_BIN_EXE_d01")),
+    ("d02", env!("CARGO_BIN_EXE_d02")),
+    ("d03", env!("CARGO_BIN_EXE_d03")),
+    ("d04", env!("CARGO_BIN_EXE_d04")),
+    ("d05", env!("CARGO_BIN_EXE_d05")),
+    ("d06", env!("CARGO_BIN_EXE_d06")),
+    ("d07", env!("CARGO_BIN_EXE_d07")),
+    ("d08", env!("CARGO_BIN_EXE_d08")),
+    ("d09", env!("CARGO_BIN_EXE_d09")),
+    ("d10", env!("CARGO_BIN_EXE_d10")),
+    ("d11", env!("CARGO_BIN_EXE_d11")),
+    ("d12", env!("CARGO_BIN_EXE_d12")),
+    ("d13", env!("CARGO_BIN_EXE_d13")),
+    ("d14", env!("CARGO_BIN_EXE_d14")),
+    ("d15", env!("CARGO_BIN_EXE_d15")),
+    ("d16", env!("CARGO_BIN_EXE_d16")),
+    ("d17", env!("CARGO_BIN_EXE_d17")),
+    ("d18", env!("CARGO_BIN_EXE_d18")),
+    ("d19", env!("CARGO_BIN_EXE_d19")),
+    ("d20", env!("CARGO_BIN_EXE_d20")),
+    ("d21", env!("CARGO_BIN_EXE_d21")),
+    ("d22", env!("CARGO_BIN_EXE_d22")),
+    ("d23", env!("CARGO_BIN_EXE_d23")),
+    ("d24", env!("CARGO_BIN_EXE_d24")),
+    ("d25", env!("CARGO_BIN_EXE_d25")),
+];
+
+// parts taking seconds (or more) per run
+const HEAVY: &[(&str, &str, u8)] = &[
+    ("d12", "--input", 2),
+    ("d16", "--input", 2),
+    ("d19", "--sample", 1),
+    ("d19", "--sample", 2),
+    ("d19", "--input", 1),
+    ("d19", "--input", 2),
+    ("d23", "--input", 2),
+];
+
+// parts the solution cannot solve
+const BROKEN: &[(&str, &str, u8, &str)] = &[
+    ("d17", "--sample", 2, "does not terminate"),
+    (
+        "d22",
+        "--sample",
+        2,
+        "cube net of the real input is hardcoded",
+    ),
+];
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let heavy = args.iter().any(|arg| arg == "--heavy");
+    let filters = args
+        .iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .collect::<Vec<_>>();
+
+    for (name, binary) in DAYS {
+        if !filters.is_empty() && !filters.iter().any(|filter| name.contains(filter.as_str())) {
+            continue;
+        }
+        println!("{name}");
+        for input in ["--sample", "--input"] {
+            // parsing is the same for both parts, timed with the first one run
+            let mut parse_timed = false;
+            for part in [1, 2] {
+                let label = format!("{} part {part}", &input[2..]);
+                let broken = BROKEN.iter().find(|(day, broken_input, broken_part, _)| {
+                    (*day, *broken_input, *broken_part) == (name, input, part)
+                });
+                if let Some((_, _, _, reason)) = broken {
+                    println!("  {label}: skipped, {reason}");
+                    continue;
+                }
+                if !heavy && HEAVY.contains(&(name, input, part)) {
+                    println!("  {label}: skipped, heavy (run with `-- --heavy`)");
+                    continue;
+                }
+                parse_timed |= bench(binary, input, part, &label, !parse_timed);
+            }
+        }
+    }
+}
+
+// prints timings of the part measured by the day binary, returns whether it succeeded
+fn bench(binary: &str, input: &str, part: u8, label: &str, with_parse: bool) -> bool {
+    let output = Command::new(binary)
+        .args(["bench", &part.to_string(), input])
+        .output()
+        .unwrap();
+    if !output.status.success() {
+        println!("  {label}: failed");
+        return false;
+    }
+    // first line names the input, then timing of parsing and of the part
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let timings = stdout.lines().skip(if with_parse { 1 } else { 2 });
+    for timing in timings {
+        println!("  {} {timing}", &input[2..]);
+    }
+    true
+}
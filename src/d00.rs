@@ -1,6 +1,6 @@
 // #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 0;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 0);
 
 type Res = u32;
 
@@ -1,30 +1,414 @@
 #![warn(clippy::pedantic)]
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, BufRead};
+
 mod utils;
-const DAY_ID: utils::DayIdType = 1;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 1);
+
+// calories of single item as well as of a whole elf, wide enough for stress inputs
+type Calories = u64;
+
+// count of the most caloric elves summed in part 2, can be changed in REPL with `set top=<k>`
+const TOP: usize = 3;
+
+// items carried by single elf
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Elf {
+    items: usize,
+    total: Calories,
+}
+
+// sums calories line by line, every blank line finishes the total of an elf
+#[derive(Debug, Default)]
+struct ElfTotals {
+    current: Option<Elf>,
+}
+
+impl ElfTotals {
+    // returns the elf finished by the line
+    pub fn push(&mut self, line: &str) -> Option<Elf> {
+        let line = line.trim();
+        if line.is_empty() {
+            return self.current.take();
+        }
+        let calories = line.parse::<Calories>().unwrap();
+        let elf = self.current.unwrap_or_default();
+        let total = elf.total.checked_add(calories);
+        self.current = Some(Elf {
+            items: elf.items + 1,
+            total: total.expect("total calories of an elf overflow"),
+        });
+        None
+    }
+
+    // the last elf, not followed by a blank line
+    pub fn finish(self) -> Option<Elf> {
+        self.current
+    }
+}
+
+// the most caloric elves as (index, total), highest total first, ties by lower index
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TopElves {
+    elves: Vec<(usize, Calories)>,
+    sum: Calories,
+}
+
+// keeps just the best `k` elves seen so far in min-heap of size `k`
+struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<(Calories, Reverse<usize>)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn push(&mut self, index: usize, total: Calories) {
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
 
-type Res = u32;
+    pub fn into_top(self) -> TopElves {
+        let elves = self
+            .heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect::<Vec<_>>();
+        let sum = elves
+            .iter()
+            .try_fold(0, |sum: Calories, (_, total)| sum.checked_add(*total));
+        TopElves {
+            elves,
+            sum: sum.expect("sum of top elves overflows"),
+        }
+    }
+}
+
+fn top_elves(totals: &[Calories], k: usize) -> TopElves {
+    let mut top = TopK::new(k);
+    for (index, total) in totals.iter().enumerate() {
+        top.push(index, *total);
+    }
+    top.into_top()
+}
+
+// items of every elf are summed as they are read
+fn parse_inventory(data: &str) -> Vec<Elf> {
+    let mut elves = ElfTotals::default();
+    let mut inventory = data
+        .lines()
+        .filter_map(|line| elves.push(line))
+        .collect::<Vec<_>>();
+    inventory.extend(elves.finish());
+    inventory
+}
+
+// total calories of every elf
+fn parse_input(data: &str) -> Vec<Calories> {
+    parse_inventory(data).iter().map(|elf| elf.total).collect()
+}
+
+fn solve_part1(data: &[Calories]) -> Calories {
+    top_elves(data, 1).sum
+}
+
+fn solve_part2(data: &[Calories]) -> Calories {
+    top_elves(data, utils::params::get("top", TOP)).sum
+}
+
+// elves are read one at a time, only the highest totals are kept
+fn stream_input(
+    reader: &mut dyn BufRead,
+    report: &mut dyn FnMut(utils::StageResult),
+) -> io::Result<()> {
+    // at least the best elf is kept for part 1 even when part 2 sums none
+    let k = utils::params::get("top", TOP);
+    let (top, elapsed) = timed!({
+        let mut top = TopK::new(k.max(1));
+        for (index, block) in utils::stream::blocks(reader).enumerate() {
+            let mut elves = ElfTotals::default();
+            for line in block? {
+                elves.push(&line);
+            }
+            if let Some(elf) = elves.finish() {
+                top.push(index, elf.total);
+            }
+        }
+        top.into_top()
+    });
+    report(utils::StageResult::new(utils::Stage::Parse, None, elapsed));
+
+    // no elves carry no calories, as in the input solved as a whole
+    let (result, elapsed) = timed!(top.elves.first().map_or(0, |elf| elf.1));
+    report(utils::StageResult::new(
+        utils::Stage::Part(1),
+        Some(result.to_string()),
+        elapsed,
+    ));
+    let (result, elapsed) = timed!(top.elves.iter().take(k).map(|elf| elf.1).sum::<Calories>());
+    report(utils::StageResult::new(
+        utils::Stage::Part(2),
+        Some(result.to_string()),
+        elapsed,
+    ));
+
+    Ok(())
+}
+
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let k = if part == 1 {
+        1
+    } else {
+        utils::params::get("top", TOP)
+    };
+    let top = top_elves(&parse_input(data_str), k);
+    let mut elves = utils::explain::Artifact::new(
+        format!("{k} most caloric elves, {} calories", top.sum),
+        &["elf", "calories"],
+    );
+    for (index, total) in &top.elves {
+        elves.row(&[&(index + 1), total]);
+    }
+    vec![elves]
+}
 
-fn parse_input(data: &str) -> Vec<Vec<u32>> {
-    let mut res = vec![];
+#[allow(clippy::cast_precision_loss)]
+fn as_float(calories: Calories) -> f64 {
+    calories as f64
+}
 
-    for group in data.split("\n\n") {
-        let s = group.lines().map(|x| x.parse::<u32>().unwrap()).collect();
-        res.push(s);
+// value at the quarter of sorted values, interpolated between neighbors
+fn quartile(sorted: &[Calories], quarter: usize) -> f64 {
+    let position = (sorted.len() - 1) * quarter;
+    let (below, remainder) = (position / 4, position % 4);
+    let low = as_float(sorted[below]);
+    if remainder == 0 {
+        return low;
     }
+    let high = as_float(sorted[below + 1]);
+    low + (high - low) * as_float(Calories::try_from(remainder).unwrap()) / 4.0
+}
 
-    res
+#[derive(Debug, Clone, PartialEq)]
+struct Statistics {
+    mean: f64,
+    median: f64,
+    std_dev: f64,
+    // totals outside of these are outliers, 1.5 interquartile range from the quartiles
+    fences: (f64, f64),
+}
+
+fn statistics(totals: &[Calories]) -> Statistics {
+    let mut sorted = totals.to_vec();
+    sorted.sort_unstable();
+    let count = as_float(Calories::try_from(totals.len()).unwrap());
+    let mean = totals.iter().copied().map(as_float).sum::<f64>() / count;
+    let variance = totals
+        .iter()
+        .map(|total| (as_float(*total) - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    let (q1, q3) = (quartile(&sorted, 1), quartile(&sorted, 3));
+    let iqr = q3 - q1;
+    Statistics {
+        mean,
+        median: quartile(&sorted, 2),
+        std_dev: variance.sqrt(),
+        fences: (q1 - 1.5 * iqr, q3 + 1.5 * iqr),
+    }
 }
 
-fn solve_part1(data: &[Vec<u32>]) -> Res {
-    data.iter().map(|v| v.iter().sum()).max().unwrap()
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+// counts of elves in ranges of totals of the same width, as (first, last, count)
+fn histogram(totals: &[Calories]) -> Vec<(Calories, Calories, usize)> {
+    let (min, max) = (totals.iter().min().unwrap(), totals.iter().max().unwrap());
+    let bins = Calories::try_from(HISTOGRAM_BINS.min(totals.len())).unwrap();
+    let width = (max - min + 1).div_ceil(bins);
+    (0..bins)
+        .map(|bin| {
+            let first = min + bin * width;
+            let last = first + width - 1;
+            let count = totals
+                .iter()
+                .filter(|total| (first..=last).contains(*total))
+                .count();
+            (first, last, count)
+        })
+        .collect()
 }
 
-fn solve_part2(data: &[Vec<u32>]) -> Res {
-    let mut v: Vec<u32> = data.iter().map(|v| v.iter().sum()).collect();
-    v.sort_unstable();
-    v.iter().rev().take(3).sum()
+// calorie inventory of all the elves as tables
+fn report_input(data_str: &str) -> Vec<utils::explain::Artifact> {
+    use utils::explain::Artifact;
+
+    let inventory = parse_inventory(data_str);
+    let totals = inventory.iter().map(|elf| elf.total).collect::<Vec<_>>();
+    let items = inventory.iter().map(|elf| elf.items).sum::<usize>();
+    let mut summary = Artifact::new("summary", &["statistic", "value"]);
+    summary.row(&[&"elves", &inventory.len()]);
+    summary.row(&[&"items", &items]);
+    if inventory.is_empty() {
+        return vec![summary];
+    }
+
+    let stats = statistics(&totals);
+    summary.row(&[&"calories", &totals.iter().sum::<Calories>()]);
+    summary.row(&[&"mean", &format!("{:.1}", stats.mean)]);
+    summary.row(&[&"median", &format!("{:.1}", stats.median)]);
+    summary.row(&[&"standard deviation", &format!("{:.1}", stats.std_dev)]);
+    summary.row(&[&"min", totals.iter().min().unwrap()]);
+    summary.row(&[&"max", totals.iter().max().unwrap()]);
+
+    let mut elves = Artifact::new("elves", &["elf", "items", "calories"]);
+    for (index, elf) in inventory.iter().enumerate() {
+        elves.row(&[&(index + 1), &elf.items, &elf.total]);
+    }
+
+    let bins = histogram(&totals);
+    let most = bins.iter().map(|(_, _, count)| *count).max().unwrap();
+    let mut histogram = Artifact::new("histogram", &["calories", "elves", "bar"]);
+    for (first, last, count) in bins {
+        let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+        histogram.row(&[&format!("{first}-{last}"), &count, &bar]);
+    }
+
+    let (low, high) = stats.fences;
+    let mut outliers = Artifact::new(
+        format!("outliers, totals outside {low:.1} to {high:.1}"),
+        &["elf", "calories", "deviations from mean"],
+    );
+    for (index, total) in totals.iter().enumerate() {
+        let total_float = as_float(*total);
+        if total_float < low || total_float > high {
+            let deviations = (total_float - stats.mean) / stats.std_dev;
+            outliers.row(&[&(index + 1), total, &format!("{deviations:+.2}")]);
+        }
+    }
+
+    vec![summary, elves, histogram, outliers]
 }
 
-generate_main!();
+generate_main!(
+    stream = stream_input,
+    explain = explain_input,
+    report = report_input
+);
 
 generate_tests!(24000, 45000);
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    #[test]
+    fn stream_variants() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::text::assert_same_on_variants(&data_str, |data| {
+            utils::stream::answers(stream_input, data).unwrap()
+        });
+    }
+
+    #[test]
+    fn stream_test() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let mut answers = vec![];
+        stream_input(&mut data_str.as_bytes(), &mut |result| {
+            answers.extend(result.answer);
+        })
+        .unwrap();
+        assert_eq!(answers, ["24000", "45000"]);
+    }
+
+    #[test]
+    fn stream_of_empty_input() {
+        let answers = utils::stream::answers(stream_input, "").unwrap();
+        assert_eq!(answers, ["0", "0"]);
+        let totals = parse_input("");
+        assert_eq!((solve_part1(&totals), solve_part2(&totals)), (0, 0));
+    }
+
+    #[test]
+    fn stream_splits_elves_on_blank_lines() {
+        let mut answers = vec![];
+        stream_input(&mut "1\n2\n\n\n4\n".as_bytes(), &mut |result| {
+            answers.extend(result.answer);
+        })
+        .unwrap();
+        assert_eq!(answers, ["4", "7"]);
+    }
+
+    #[test]
+    fn stream_agrees_on_top_count() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let totals = parse_input(&data_str);
+        for top in [0, 1, 2, 10] {
+            utils::params::set("top", top);
+            let answers = utils::stream::answers(stream_input, &data_str).unwrap();
+            let solved = [solve_part1(&totals), solve_part2(&totals)].map(|sum| sum.to_string());
+            assert_eq!(answers, solved, "top={top}");
+        }
+        utils::params::unset("top");
+    }
+}
+
+#[cfg(test)]
+mod report_tests {
+    use super::*;
+
+    #[test]
+    fn sample_report() {
+        let artifacts = report_input(&utils::string_from_sample(DAY_ID));
+        assert_eq!(
+            artifacts[0].column("value"),
+            ["5", "10", "55000", "11000.0", "10000.0", "6985.7", "4000", "24000"]
+        );
+        assert_eq!(artifacts[1].column("items"), ["3", "1", "2", "3", "1"]);
+        assert_eq!(artifacts[2].column("elves").len(), 5);
+        assert_eq!(artifacts[3].column("elf"), ["4"]);
+        assert_eq!(artifacts[3].column("deviations from mean"), ["+1.86"]);
+    }
+}
+
+#[cfg(test)]
+mod top_tests {
+    use super::*;
+
+    #[test]
+    fn top_elves_with_indices() {
+        let totals = parse_input(&utils::string_from_sample(DAY_ID));
+        assert_eq!(
+            top_elves(&totals, 3),
+            TopElves {
+                elves: vec![(3, 24000), (2, 11000), (4, 10000)],
+                sum: 45000,
+            }
+        );
+        assert_eq!(top_elves(&totals, 10).elves.len(), totals.len());
+        assert_eq!(top_elves(&totals, 0).sum, 0);
+    }
+
+    #[test]
+    fn ties_prefer_earlier_elves() {
+        let top = top_elves(&[5, 7, 5, 7], 3);
+        assert_eq!(top.elves, [(1, 7), (3, 7), (0, 5)]);
+    }
+
+    #[test]
+    fn totals_beyond_u32() {
+        let data = format!("{}\n{}\n\n1", u32::MAX, u32::MAX);
+        let totals = parse_input(&data);
+        assert_eq!(totals, [2 * Calories::from(u32::MAX), 1]);
+        assert_eq!(solve_part2(&totals), 2 * Calories::from(u32::MAX) + 1);
+    }
+}
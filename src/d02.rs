@@ -1,123 +1,824 @@
+use itertools::Itertools;
+use std::cmp::Ordering;
+
 mod utils;
-const DAY_ID: utils::DayIdType = 2;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 2);
 
 type Res = u32;
 
-#[derive(Debug, Copy, Clone)]
+// cyclic game of an odd number of moves, every move beats the half of the other moves
+// preceding it in the cycle and loses to the half following it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rules {
+    // in the order of the cycle
+    moves: Vec<String>,
+    move_scores: Vec<Res>,
+    // symbols of the moves in the first column of the guide
+    opponent_symbols: Vec<String>,
+    // symbols of the moves in the second column, as read in part 1
+    own_symbols: Vec<String>,
+    // symbols of the outcomes in the second column, as read in part 2, from the move
+    // losing the most (the one following the opponent's by half of the cycle) to the move
+    // winning the most (the one preceding it by half of the cycle)
+    outcome_symbols: Vec<String>,
+    outcome_scores: Vec<(HandResult, Res)>,
+}
+
+const RESULTS: [(&str, HandResult); 3] = [
+    ("loss", HandResult::Loss),
+    ("draw", HandResult::Draw),
+    ("win", HandResult::Win),
+];
+
+// definitions of the games guides can be written for, smaller games first: a line
+// `<move> <score> <opponent's symbol> <own symbol>` per move in the order of the cycle,
+// `outcomes` with their symbols and `results` scoring every result of a round
+const GAMES: [&str; 2] = [
+    "\
+Rock 1 A X
+Paper 2 B Y
+Scissors 3 C Z
+outcomes X Y Z
+results loss:0 draw:3 win:6",
+    "\
+Rock 1 A X
+Spock 5 E W
+Paper 2 B Y
+Lizard 4 D V
+Scissors 3 C Z
+outcomes V W X Y Z
+results loss:0 draw:3 win:6",
+];
+
+// distinct symbols of a column of the guide
+fn column_symbols(data: &str, column: usize) -> Vec<&str> {
+    data.lines()
+        .filter_map(|line| line.split_whitespace().nth(column))
+        .unique()
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum HandResult {
     Win,
     Draw,
     Loss,
 }
 
-impl HandResult {
-    pub fn score(self) -> Res {
-        match self {
-            HandResult::Win => 6,
-            HandResult::Draw => 3,
-            HandResult::Loss => 0,
+// index of the move in the cycle of the rules
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Hand(usize);
+
+impl Rules {
+    // describes the first invalid line of the definition
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let mut rules = Rules {
+            moves: vec![],
+            move_scores: vec![],
+            opponent_symbols: vec![],
+            own_symbols: vec![],
+            outcome_symbols: vec![],
+            outcome_scores: vec![],
+        };
+        for (index, line) in definition.lines().enumerate() {
+            let error = |message: &str| format!("line {}: {message}", index + 1);
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            if let ["outcomes", symbols @ ..] = words.as_slice() {
+                rules.outcome_symbols = symbols.iter().map(ToString::to_string).collect();
+                continue;
+            }
+            if let ["results", scores @ ..] = words.as_slice() {
+                for score in scores {
+                    let parsed = score.split_once(':').and_then(|(name, score)| {
+                        let (_, result) = RESULTS.iter().find(|(n, _)| *n == name)?;
+                        Some((*result, score.parse().ok()?))
+                    });
+                    let Some((result, score)) = parsed else {
+                        return Err(error(&format!("invalid result score `{score}`")));
+                    };
+                    rules.outcome_scores.push((result, score));
+                }
+                continue;
+            }
+            let &[name, score, opponent, own] = words.as_slice() else {
+                return Err(error("expected move, score and two symbols"));
+            };
+            let score = score.parse().map_err(|_| error("invalid score"))?;
+            for (symbols, symbol) in [
+                (&rules.moves, name),
+                (&rules.opponent_symbols, opponent),
+                (&rules.own_symbols, own),
+            ] {
+                if Rules::symbol(symbols, symbol).is_some() {
+                    return Err(error(&format!("`{symbol}` defined twice")));
+                }
+            }
+            rules.moves.push(name.to_string());
+            rules.move_scores.push(score);
+            rules.opponent_symbols.push(opponent.to_string());
+            rules.own_symbols.push(own.to_string());
+        }
+
+        let n = rules.moves.len();
+        if n.is_multiple_of(2) {
+            return Err(format!("{n} moves, the game needs an odd number of them"));
+        }
+        if rules.outcome_symbols.len() != n || rules.outcome_symbols.iter().unique().count() != n {
+            return Err(format!("expected {n} distinct outcome symbols"));
         }
+        for (name, result) in RESULTS {
+            let scored = rules.outcome_scores.iter().filter(|(r, _)| *r == result);
+            if scored.count() != 1 {
+                return Err(format!("expected a single score of {name}"));
+            }
+        }
+        Ok(rules)
+    }
+
+    // game of the given number of moves
+    pub fn of_moves(moves: usize) -> Result<Self, String> {
+        let games = GAMES.iter().map(|game| Rules::parse(game).unwrap());
+        let mut games = games.filter(|rules| rules.moves.len() == moves);
+        games
+            .next()
+            .ok_or_else(|| format!("no game of {moves} moves"))
+    }
+
+    // the smallest game knowing all the symbols of the guide, unless `moves` parameter
+    // chooses the game by its number of moves, guides fitting no game are read by the
+    // smallest one to report their unknown symbols
+    pub fn for_guide(data: &str) -> Result<Self, String> {
+        let mut games = GAMES.iter().map(|game| Rules::parse(game).unwrap());
+        let smallest = games.next().unwrap();
+        let (opponent, own) = (column_symbols(data, 0), column_symbols(data, 1));
+        let detected = std::iter::once(smallest.clone())
+            .chain(games)
+            .find(|rules| {
+                opponent
+                    .iter()
+                    .all(|symbol| Rules::symbol(&rules.opponent_symbols, symbol).is_some())
+                    && own.iter().all(|symbol| rules.knows_own_symbol(symbol))
+            })
+            .unwrap_or(smallest);
+        match utils::params::get("moves", detected.moves.len()) {
+            moves if moves == detected.moves.len() => Ok(detected),
+            moves => Rules::of_moves(moves),
+        }
+    }
+
+    fn symbol(symbols: &[String], symbol: &str) -> Option<usize> {
+        symbols.iter().position(|s| s == symbol)
+    }
+
+    // the second column is read as a move in part 1 and as an outcome in part 2
+    fn knows_own_symbol(&self, symbol: &str) -> bool {
+        Rules::symbol(&self.own_symbols, symbol).is_some()
+            && Rules::symbol(&self.outcome_symbols, symbol).is_some()
+    }
+
+    pub fn score(&self, hand: Hand, other: Hand) -> Res {
+        let result = self.against(hand, other);
+        let (_, result_score) = self
+            .outcome_scores
+            .iter()
+            .find(|(r, _)| *r == result)
+            .unwrap();
+        self.move_scores[hand.0] + result_score
+    }
+
+    pub fn against(&self, hand: Hand, other: Hand) -> HandResult {
+        let n = self.moves.len();
+        match (hand.0 + n - other.0) % n {
+            0 => HandResult::Draw,
+            ahead if ahead <= n / 2 => HandResult::Win,
+            _ => HandResult::Loss,
+        }
+    }
+
+    // hand to play against the other for the outcome, indexed as `outcome_symbols`
+    pub fn hand_for(&self, other: Hand, outcome: usize) -> Hand {
+        let n = self.moves.len();
+        Hand((other.0 + n + outcome - n / 2) % n)
     }
 
-    pub fn from_hand(hand: Hand) -> Self {
-        match hand {
-            Hand::Rock => HandResult::Loss,
-            Hand::Paper => HandResult::Draw,
-            Hand::Scissors => HandResult::Win,
+    // e.g. `loss`, `draw` and `win` of 3 moves, `win 2` as 2 moves ahead in bigger games
+    pub fn outcome_name(&self, outcome: usize) -> String {
+        let half = self.moves.len() / 2;
+        let (name, distance) = match outcome.cmp(&half) {
+            Ordering::Less => ("loss", half - outcome),
+            Ordering::Equal => return "draw".to_string(),
+            Ordering::Greater => ("win", outcome - half),
+        };
+        if half == 1 {
+            name.to_string()
+        } else {
+            format!("{name} {distance}")
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Hand {
-    Rock,
-    Paper,
-    Scissors,
+// opponent's moves with the unknown second column of the strategy guide
+#[derive(Debug)]
+struct Guide<'a> {
+    rules: Rules,
+    rounds: Vec<(Hand, &'a str)>,
 }
 
-impl From<&str> for Hand {
-    fn from(s: &str) -> Self {
-        match s {
-            "A" | "X" => Hand::Rock,
-            "B" | "Y" => Hand::Paper,
-            "C" | "Z" => Hand::Scissors,
-            _ => unreachable!(),
+// describes the first invalid line of the guide
+fn parse_guide(data: &str) -> Result<Guide<'_>, String> {
+    read_guide(Rules::for_guide(data)?, data)
+}
+
+// guide of the given game
+fn read_guide(rules: Rules, data: &str) -> Result<Guide<'_>, String> {
+    let rounds = data
+        .lines()
+        .enumerate()
+        .map(|(index, x)| {
+            let &[enemy, me] = x.split_whitespace().collect::<Vec<_>>().as_slice() else {
+                return Err(format!("line {}: expected two moves", index + 1));
+            };
+            let Some(enemy) = Rules::symbol(&rules.opponent_symbols, enemy) else {
+                return Err(format!(
+                    "line {}: unknown opponent's move `{enemy}`",
+                    index + 1
+                ));
+            };
+            if Rules::symbol(&rules.own_symbols, me).is_none() {
+                return Err(format!("line {}: unknown own move `{me}`", index + 1));
+            }
+            if Rules::symbol(&rules.outcome_symbols, me).is_none() {
+                return Err(format!("line {}: unknown outcome `{me}`", index + 1));
+            }
+            Ok((Hand(enemy), me))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Guide { rules, rounds })
+}
+
+fn parse_input(data: &str) -> Guide<'_> {
+    parse_guide(data).unwrap()
+}
+
+// score of the guide with the second column read as moves, `mapping[i]` is the move of
+// `own_symbols[i]`
+fn score_as_moves(data: &Guide, mapping: &[usize]) -> Res {
+    let rules = &data.rules;
+    data.rounds
+        .iter()
+        .map(|(enemy, symbol)| {
+            let me = Hand(mapping[Rules::symbol(&rules.own_symbols, symbol).unwrap()]);
+            rules.score(me, *enemy)
+        })
+        .sum()
+}
+
+// score of the guide with the second column read as outcomes, `mapping[i]` is the outcome
+// of `outcome_symbols[i]`
+fn score_as_outcomes(data: &Guide, mapping: &[usize]) -> Res {
+    let rules = &data.rules;
+    data.rounds
+        .iter()
+        .map(|(enemy, symbol)| {
+            let outcome = mapping[Rules::symbol(&rules.outcome_symbols, symbol).unwrap()];
+            let me = rules.hand_for(*enemy, outcome);
+            rules.score(me, *enemy)
+        })
+        .sum()
+}
+
+fn solve_part1(data: &Guide) -> Res {
+    let identity = (0..data.rules.moves.len()).collect::<Vec<_>>();
+    score_as_moves(data, &identity)
+}
+
+fn solve_part2(data: &Guide) -> Res {
+    // "fix" parsing of data for new elf instructions...
+    let identity = (0..data.rules.moves.len()).collect::<Vec<_>>();
+    score_as_outcomes(data, &identity)
+}
+
+// every way of reading the second column as moves or outcomes, with its score, the
+// best first (ties in the order of mappings), marked with the part reading it so
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Interpretation {
+    part: u8,
+    mapping: Vec<usize>,
+    score: Res,
+}
+
+fn interpretations(data: &Guide, part: u8) -> Vec<Interpretation> {
+    let n = data.rules.moves.len();
+    let mut all = (0..n)
+        .permutations(n)
+        .map(|mapping| {
+            let score = match part {
+                1 => score_as_moves(data, &mapping),
+                _ => score_as_outcomes(data, &mapping),
+            };
+            Interpretation {
+                part,
+                mapping,
+                score,
+            }
+        })
+        .collect::<Vec<_>>();
+    all.sort_by_key(|interpretation| std::cmp::Reverse(interpretation.score));
+    all
+}
+
+// e.g. `X=Rock Y=Paper Z=Scissors`, ordered by symbols
+fn mapping_name(rules: &Rules, interpretation: &Interpretation) -> String {
+    let (symbols, name): (_, &dyn Fn(usize) -> String) = match interpretation.part {
+        1 => (&rules.own_symbols, &|hand| rules.moves[hand].clone()),
+        _ => (&rules.outcome_symbols, &|outcome| {
+            rules.outcome_name(outcome)
+        }),
+    };
+    symbols
+        .iter()
+        .zip(&interpretation.mapping)
+        .sorted()
+        .map(|(symbol, value)| format!("{symbol}={}", name(*value)))
+        .join(" ")
+}
+
+// best, worst and expected (over equally likely mappings) scores of the guide read as moves
+// and as outcomes, with all the mappings
+fn report_input(data_str: &str) -> Vec<utils::explain::Artifact> {
+    use utils::explain::Artifact;
+
+    let data = parse_input(data_str);
+    let mut summary = Artifact::new(
+        "interpretations of the second column",
+        &[
+            "as",
+            "mappings",
+            "best",
+            "best mapping",
+            "worst",
+            "worst mapping",
+            "expected",
+        ],
+    );
+    let mut artifacts = vec![];
+    for (part, reading) in [(1, "moves"), (2, "outcomes")] {
+        let all = interpretations(&data, part);
+        let (best, worst) = (all.first().unwrap(), all.last().unwrap());
+        let total = all
+            .iter()
+            .map(|interpretation| interpretation.score)
+            .sum::<Res>();
+        let expected = f64::from(total) / f64::from(u32::try_from(all.len()).unwrap());
+        summary.row(&[
+            &reading,
+            &all.len(),
+            &best.score,
+            &mapping_name(&data.rules, best),
+            &worst.score,
+            &mapping_name(&data.rules, worst),
+            &format!("{expected:.1}"),
+        ]);
+
+        let mut mappings = Artifact::new(
+            format!("second column as {reading}"),
+            &["mapping", "score", "puzzle"],
+        );
+        for interpretation in &all {
+            let identity = interpretation
+                .mapping
+                .iter()
+                .enumerate()
+                .all(|(i, v)| i == *v);
+            let puzzle = if identity {
+                format!("part {part}")
+            } else {
+                String::new()
+            };
+            mappings.row(&[
+                &mapping_name(&data.rules, interpretation),
+                &interpretation.score,
+                &puzzle,
+            ]);
         }
+        artifacts.push(mappings);
     }
+    artifacts.insert(0, summary);
+    artifacts
 }
 
-impl Hand {
-    pub fn score(self) -> Res {
-        match self {
-            Hand::Rock => 1,
-            Hand::Paper => 2,
-            Hand::Scissors => 3,
+// how a player of a tournament chooses moves
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Strategy {
+    Fixed(Hand),
+    // moves in the order of the cycle of the rules, from the first one
+    Cyclic,
+    // beats the last move of the opponent, the first move in the first round
+    CounterLastMove,
+    // own moves of a strategy guide, read as in part 1, repeated when exhausted
+    Guide(Vec<Hand>),
+}
+
+impl Strategy {
+    pub fn from_guide(guide: &Guide) -> Self {
+        let rules = &guide.rules;
+        let moves = guide.rounds.iter();
+        let moves =
+            moves.map(|(_, symbol)| Hand(Rules::symbol(&rules.own_symbols, symbol).unwrap()));
+        Strategy::Guide(moves.collect())
+    }
+
+    // `fixed:<move>`, `cyclic`, `counter` or path to strategy guide file
+    pub fn parse(rules: &Rules, spec: &str) -> Result<Self, String> {
+        if let Some(name) = spec.strip_prefix("fixed:") {
+            let hand = rules
+                .moves
+                .iter()
+                .position(|m| m.eq_ignore_ascii_case(name));
+            let hand = hand.ok_or_else(|| format!("{spec}: unknown move `{name}`"))?;
+            return Ok(Strategy::Fixed(Hand(hand)));
+        }
+        match spec {
+            "cyclic" => Ok(Strategy::Cyclic),
+            "counter" => Ok(Strategy::CounterLastMove),
+            path => {
+                let data = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+                let data = utils::text::normalize(&data);
+                let guide = read_guide(rules.clone(), &data).map_err(|e| format!("{path}: {e}"))?;
+                if guide.rounds.is_empty() {
+                    return Err(format!("{path}: no moves"));
+                }
+                Ok(Self::from_guide(&guide))
+            }
         }
     }
 
-    pub fn against(self, other: Hand) -> HandResult {
+    pub fn play(&self, rules: &Rules, round: usize, opponents_last: Option<Hand>) -> Hand {
         match self {
-            Hand::Rock => match other {
-                Hand::Rock => HandResult::Draw,
-                Hand::Paper => HandResult::Loss,
-                Hand::Scissors => HandResult::Win,
-            },
-            Hand::Paper => match other {
-                Hand::Rock => HandResult::Win,
-                Hand::Paper => HandResult::Draw,
-                Hand::Scissors => HandResult::Loss,
-            },
-            Hand::Scissors => match other {
-                Hand::Rock => HandResult::Loss,
-                Hand::Paper => HandResult::Win,
-                Hand::Scissors => HandResult::Draw,
+            Strategy::Fixed(hand) => *hand,
+            Strategy::Cyclic => Hand(round % rules.moves.len()),
+            Strategy::CounterLastMove => match opponents_last {
+                Some(last) => Hand((last.0 + 1) % rules.moves.len()),
+                None => Hand(0),
             },
+            Strategy::Guide(moves) => moves[round % moves.len()],
         }
     }
+}
 
-    pub fn opponents_hand(self, opponents_result: HandResult) -> Hand {
-        match opponents_result {
-            HandResult::Loss => match self {
-                Hand::Rock => Hand::Scissors,
-                Hand::Paper => Hand::Rock,
-                Hand::Scissors => Hand::Paper,
-            },
-            HandResult::Draw => self,
-            HandResult::Win => match self {
-                Hand::Rock => Hand::Paper,
-                Hand::Paper => Hand::Scissors,
-                Hand::Scissors => Hand::Rock,
-            },
+// rounds won, drawn and lost by the first player with scores of both players
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Match {
+    won: usize,
+    drawn: usize,
+    lost: usize,
+    score: Res,
+    opponents_score: Res,
+}
+
+impl Match {
+    pub fn play(rules: &Rules, first: &Strategy, second: &Strategy, rounds: usize) -> Self {
+        let mut result = Match::default();
+        let mut last = None;
+        for round in 0..rounds {
+            let (first_last, second_last) = last.unzip();
+            let hands = (
+                first.play(rules, round, second_last),
+                second.play(rules, round, first_last),
+            );
+            match rules.against(hands.0, hands.1) {
+                HandResult::Win => result.won += 1,
+                HandResult::Draw => result.drawn += 1,
+                HandResult::Loss => result.lost += 1,
+            }
+            result.score += rules.score(hands.0, hands.1);
+            result.opponents_score += rules.score(hands.1, hands.0);
+            last = Some(hands);
+        }
+        result
+    }
+
+    // winner of a match has the higher total score
+    pub fn result(&self) -> HandResult {
+        match self.score.cmp(&self.opponents_score) {
+            Ordering::Greater => HandResult::Win,
+            Ordering::Equal => HandResult::Draw,
+            Ordering::Less => HandResult::Loss,
+        }
+    }
+
+    pub fn swapped(self) -> Self {
+        Match {
+            won: self.lost,
+            lost: self.won,
+            score: self.opponents_score,
+            opponents_score: self.score,
+            ..self
         }
     }
 }
 
-fn parse_input(data: &str) -> Vec<(Hand, Hand)> {
-    data.lines()
-        .map(|x| {
-            let draws: Vec<&str> = x.split_whitespace().collect();
-            (draws[0].into(), draws[1].into())
-        })
+// players of the tournament when none are given on command line
+fn default_players(rules: &Rules) -> Vec<String> {
+    let fixed = rules.moves.iter().map(|name| format!("fixed:{name}"));
+    fixed
+        .chain(["cyclic".to_string(), "counter".to_string()])
         .collect()
 }
 
-fn solve_part1(data: &[(Hand, Hand)]) -> Res {
-    data.iter()
-        .map(|(enemy, me)| me.score() + me.against(*enemy).score())
-        .sum()
-}
+// round-robin of the input guide (`input`) and the players, every match lasts as many
+// rounds as there are in the input guide, 2 points for won match and 1 for a draw
+fn tournament_input(
+    data_str: &str,
+    players: &[String],
+) -> Result<Vec<utils::explain::Artifact>, String> {
+    use utils::explain::Artifact;
 
-fn solve_part2(data: &[(Hand, Hand)]) -> Res {
-    data.iter()
-        .map(|(enemy, me)| (enemy, HandResult::from_hand(*me)))
-        .map(|(enemy, needed_result)| {
-            // "fix" parsing of data for new elf instructions...
-            let me = enemy.opponents_hand(needed_result);
-            me.score() + me.against(*enemy).score()
-        })
-        .sum()
+    let guide = parse_guide(data_str)?;
+    let rules = &guide.rules;
+    let rounds = guide.rounds.len();
+    let players = if players.is_empty() {
+        default_players(rules)
+    } else {
+        players.to_vec()
+    };
+    let mut entrants = vec![("input".to_string(), Strategy::from_guide(&guide))];
+    for spec in players {
+        entrants.push((spec.clone(), Strategy::parse(rules, &spec)?));
+    }
+
+    let mut matches = Artifact::new(
+        format!("matches of {rounds} rounds"),
+        &[
+            "player",
+            "opponent",
+            "won",
+            "drawn",
+            "lost",
+            "score",
+            "opponent's score",
+            "winner",
+        ],
+    );
+    // per player: points, matches won, drawn and lost, total score
+    let mut standings = vec![(0, [0; 3], 0); entrants.len()];
+    for ((i, (name, first)), (j, (opponent, second))) in
+        entrants.iter().enumerate().tuple_combinations()
+    {
+        let result = Match::play(rules, first, second, rounds);
+        for (player, result) in [(i, result), (j, result.swapped())] {
+            let (points, record, score) = &mut standings[player];
+            let (gained, slot) = match result.result() {
+                HandResult::Win => (2, 0),
+                HandResult::Draw => (1, 1),
+                HandResult::Loss => (0, 2),
+            };
+            *points += gained;
+            record[slot] += 1;
+            *score += result.score;
+        }
+        let winner = match result.result() {
+            HandResult::Win => name.as_str(),
+            HandResult::Draw => "-",
+            HandResult::Loss => opponent.as_str(),
+        };
+        matches.row(&[
+            name,
+            opponent,
+            &result.won,
+            &result.drawn,
+            &result.lost,
+            &result.score,
+            &result.opponents_score,
+            &winner,
+        ]);
+    }
+
+    let mut leaderboard = Artifact::new(
+        "leaderboard",
+        &["rank", "player", "points", "won", "drawn", "lost", "score"],
+    );
+    let order = (0..entrants.len()).sorted_by_key(|player| {
+        let (points, _, score) = standings[*player];
+        std::cmp::Reverse((points, score))
+    });
+    for (rank, player) in order.enumerate() {
+        let (points, [won, drawn, lost], score) = standings[player];
+        leaderboard.row(&[
+            &(rank + 1),
+            &entrants[player].0,
+            &points,
+            &won,
+            &drawn,
+            &lost,
+            &score,
+        ]);
+    }
+    Ok(vec![leaderboard, matches])
 }
 
-generate_main!();
+generate_main!(report = report_input, tournament = tournament_input);
 
 generate_tests!(15, 12);
+
+#[cfg(test)]
+mod report_tests {
+    use super::*;
+
+    #[test]
+    fn sample_interpretations() {
+        let artifacts = report_input(&utils::string_from_sample(DAY_ID));
+        let summary = &artifacts[0];
+        assert_eq!(summary.column("mappings"), ["6", "6"]);
+        assert_eq!(summary.column("best"), ["24", "18"]);
+        assert_eq!(
+            summary.column("best mapping"),
+            ["X=Scissors Y=Paper Z=Rock", "X=win Y=loss Z=draw"]
+        );
+        assert_eq!(summary.column("worst"), ["6", "12"]);
+        assert_eq!(summary.column("expected"), ["15.0", "15.0"]);
+
+        let puzzle = |artifact: &utils::explain::Artifact, part| {
+            let row = artifact.column("puzzle").iter().position(|p| *p == part);
+            artifact.column("score")[row.unwrap()].to_string()
+        };
+        assert_eq!(puzzle(&artifacts[1], "part 1"), "15");
+        assert_eq!(puzzle(&artifacts[2], "part 2"), "12");
+    }
+}
+
+#[cfg(test)]
+mod tournament_tests {
+    use super::*;
+
+    #[test]
+    fn strategies() {
+        let rules = &Rules::of_moves(3).unwrap();
+        let counter = Strategy::CounterLastMove;
+        let cyclic = Strategy::Cyclic;
+        // beating the previous move of cyclic is playing the same move as it does
+        let result = Match::play(rules, &counter, &cyclic, 4);
+        assert_eq!((result.won, result.drawn, result.lost), (0, 4, 0));
+        // draw in the first round, then paper covers rock
+        let rock = Strategy::parse(rules, "fixed:rock").unwrap();
+        let result = Match::play(rules, &rock, &counter, 4);
+        assert_eq!((result.won, result.drawn, result.lost), (0, 1, 3));
+        assert_eq!((result.score, result.opponents_score), (4 + 3, 4 + 3 * 8));
+        assert_eq!(result.swapped().won, 3);
+        assert!(Strategy::parse(rules, "fixed:lizard").is_err());
+    }
+
+    #[test]
+    fn sample_tournament() {
+        let players = ["fixed:Paper".to_string(), "counter".to_string()];
+        let artifacts = tournament_input(&utils::string_from_sample(DAY_ID), &players).unwrap();
+        let leaderboard = &artifacts[0];
+        assert_eq!(
+            leaderboard.column("player"),
+            ["input", "counter", "fixed:Paper"]
+        );
+        assert_eq!(leaderboard.column("points"), ["3", "2", "1"]);
+        assert_eq!(artifacts[1].column("winner"), ["-", "input", "counter"]);
+        assert_eq!(artifacts[1].column("score"), ["15", "24", "12"]);
+    }
+
+    #[test]
+    fn invalid_player() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let error = tournament_input(&data_str, &["no/such/guide.txt".to_string()]);
+        assert!(error.unwrap_err().starts_with("no/such/guide.txt: "));
+        assert_eq!(
+            parse_guide("A Y\nB Q").unwrap_err(),
+            "line 2: unknown own move `Q`"
+        );
+    }
+}
+
+#[cfg(test)]
+mod rules_tests {
+    use super::*;
+
+    fn hand(rules: &Rules, name: &str) -> Hand {
+        Hand(Rules::symbol(&rules.moves, name).unwrap())
+    }
+
+    #[test]
+    fn games_are_defined() {
+        for game in GAMES {
+            let rules = Rules::parse(game).unwrap();
+            let n = rules.moves.len();
+            for symbols in [
+                &rules.opponent_symbols,
+                &rules.own_symbols,
+                &rules.outcome_symbols,
+            ] {
+                assert_eq!(symbols.len(), n);
+            }
+            assert_eq!(rules.move_scores.len(), n);
+        }
+        assert_eq!(
+            Rules::of_moves(3).unwrap().moves,
+            ["Rock", "Paper", "Scissors"]
+        );
+        assert_eq!(Rules::of_moves(4).unwrap_err(), "no game of 4 moves");
+    }
+
+    #[test]
+    fn invalid_definitions() {
+        assert_eq!(
+            Rules::parse("Rock 1 A X\nPaper 2 B\noutcomes X Y").unwrap_err(),
+            "line 2: expected move, score and two symbols"
+        );
+        assert_eq!(
+            Rules::parse("Rock one A X").unwrap_err(),
+            "line 1: invalid score"
+        );
+        assert_eq!(
+            Rules::parse("Rock 1 A X\nPaper 2 A Y").unwrap_err(),
+            "line 2: `A` defined twice"
+        );
+        assert_eq!(
+            Rules::parse("Rock 1 A X\nPaper 2 B Y\noutcomes X Y").unwrap_err(),
+            "2 moves, the game needs an odd number of them"
+        );
+        assert_eq!(
+            Rules::parse("Rock 1 A X\noutcomes X Y").unwrap_err(),
+            "expected 1 distinct outcome symbols"
+        );
+    }
+
+    #[test]
+    fn scores_of_results() {
+        let definition = "Rock 1 A X\noutcomes X\nresults loss:0 draw:1 win:10";
+        let rules = Rules::parse(definition).unwrap();
+        assert_eq!(rules.score(Hand(0), Hand(0)), 1 + 1);
+        assert_eq!(
+            Rules::parse("Rock 1 A X\noutcomes X\nresults loss:0 draw:3").unwrap_err(),
+            "expected a single score of win"
+        );
+        assert_eq!(
+            Rules::parse("Rock 1 A X\noutcomes X\nresults loss:0 draw:3 tie:1").unwrap_err(),
+            "line 3: invalid result score `tie:1`"
+        );
+    }
+
+    #[test]
+    fn outcome_symbols_differing_from_moves() {
+        let definition = "\
+Rock 1 A X
+Paper 2 B Y
+Scissors 3 C Z
+outcomes L D W
+results loss:0 draw:3 win:6";
+        let rules = Rules::parse(definition).unwrap();
+        assert_eq!(
+            read_guide(rules.clone(), "A Y\nB X").unwrap_err(),
+            "line 1: unknown outcome `Y`"
+        );
+        let data = read_guide(rules.clone(), "A W\nB L").unwrap_err();
+        assert_eq!(data, "line 1: unknown own move `W`");
+        assert!(!rules.knows_own_symbol("Y"));
+    }
+
+    #[test]
+    fn lizard_spock() {
+        let rules = &Rules::of_moves(5).unwrap();
+        for (winner, loser) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            let (winner, loser) = (hand(rules, winner), hand(rules, loser));
+            assert_eq!(rules.against(winner, loser), HandResult::Win);
+            assert_eq!(rules.against(loser, winner), HandResult::Loss);
+        }
+    }
+
+    #[test]
+    fn lizard_spock_guide() {
+        let data = parse_input("A Y\nE V\nD W\nC Z");
+        assert_eq!(data.rules.moves.len(), 5);
+        // paper covers rock, lizard poisons spock, lizard poisons spock, scissors draw
+        assert_eq!(solve_part1(&data), (2 + 6) + (4 + 6) + 5 + (3 + 3));
+        // spock vaporizes rock, spock smashes scissors, lizard eats paper,
+        // spock smashes scissors
+        assert_eq!(solve_part2(&data), (5 + 6) + 3 + 2 + (5 + 6));
+    }
+
+    #[test]
+    fn game_chosen_by_moves_parameter() {
+        let sample = utils::string_from_sample(DAY_ID);
+        assert_eq!(parse_input(&sample).rules.moves.len(), 3);
+        utils::params::set("moves", 5);
+        assert_eq!(parse_input(&sample).rules.moves.len(), 5);
+        utils::params::set("moves", 7);
+        assert_eq!(parse_guide(&sample).unwrap_err(), "no game of 7 moves");
+    }
+}
@@ -1,38 +1,45 @@
-use std::collections::HashSet;
+use std::ops::Range;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 3;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 3);
 
 type Res = u32;
 
+// set of item types, bit `priority - 1` for every type present
+type Items = u64;
+
+const GROUP_SIZE: usize = 3;
+
 #[derive(Debug, Clone)]
 pub struct Rucksack {
     pub contents: String,
-    comp_a: HashSet<char>,
-    comp_b: HashSet<char>,
+    comp_a: Items,
+    comp_b: Items,
 }
 
 impl From<&str> for Rucksack {
     fn from(s: &str) -> Self {
         let contents = s.to_string();
         let len = s.len();
-        let comp_a = s.chars().take(len / 2).collect();
-        let comp_b = s.chars().skip(len / 2).collect();
         Rucksack {
             contents,
-            comp_a,
-            comp_b,
+            comp_a: items(&s[..len / 2]),
+            comp_b: items(&s[len / 2..]),
         }
     }
 }
 
 impl Rucksack {
-    pub fn intersection(&self) -> Vec<char> {
-        self.comp_a.intersection(&self.comp_b).copied().collect()
+    pub fn intersection(&self) -> Items {
+        self.comp_a & self.comp_b
+    }
+
+    pub fn items(&self) -> Items {
+        self.comp_a | self.comp_b
     }
 
-    pub fn common_char(&self) -> char {
-        *self.intersection().first().unwrap()
+    pub fn common_priority(&self) -> Res {
+        priorities(self.intersection()).next().unwrap()
     }
 }
 
@@ -42,32 +49,552 @@ fn char_priority(ch: char) -> Res {
     distance + 1 + offset
 }
 
+fn items(s: &str) -> Items {
+    s.chars()
+        .fold(0, |items, ch| items | 1 << (char_priority(ch) - 1))
+}
+
+// priorities of the item types, ascending
+fn priorities(items: Items) -> impl Iterator<Item = Res> {
+    (1..=52).filter(move |priority| items & 1 << (priority - 1) != 0)
+}
+
+// group of rucksacks not sharing exactly one item type, by 1-based numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupError {
+    pub group: usize,
+    pub lines: Range<usize>,
+    pub problem: String,
+}
+
+impl std::fmt::Display for GroupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "group {} (lines {}-{}) {}",
+            self.group,
+            self.lines.start,
+            self.lines.end - 1,
+            self.problem
+        )
+    }
+}
+
+// priority of the single item type carried by all the elves of every group of the size
+fn badges(data: &[Rucksack], size: usize) -> Result<Vec<Res>, GroupError> {
+    data.chunks(size.max(1))
+        .enumerate()
+        .map(|(index, group)| {
+            let error = |problem| GroupError {
+                group: index + 1,
+                lines: index * size + 1..index * size + group.len() + 1,
+                problem,
+            };
+            if group.len() != size {
+                return Err(error(format!(
+                    "has {} rucksacks instead of {size}",
+                    group.len()
+                )));
+            }
+            let common = group
+                .iter()
+                .fold(Items::MAX, |common, rucksack| common & rucksack.items());
+            match common.count_ones() {
+                1 => Ok(priorities(common).next().unwrap()),
+                count => Err(error(format!("shares {count} item types instead of one"))),
+            }
+        })
+        .collect()
+}
+
+// rucksacks of groups of three found without knowing the order of elves, by indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Grouping {
+    Groups(Vec<[usize; 3]>),
+    // why no partition exists
+    Impossible(String),
+}
+
+// backtracking over triples sharing exactly one item type, always grouping the rucksack
+// with the fewest triples left first; there are too many triples to store for inputs of
+// real size, so they are listed lazily and just their count is kept for every rucksack
+struct GroupSearch {
+    items: Vec<Items>,
+    // rucksacks carrying the item type, ascending, by priority - 1
+    carriers: Vec<Vec<usize>>,
+    used: Vec<bool>,
+    // triples of unused rucksacks the rucksack can still form
+    left: Vec<usize>,
+    groups: Vec<[usize; 3]>,
+    explored: usize,
+}
+
+impl GroupSearch {
+    fn new(data: &[Rucksack]) -> Self {
+        let items = data.iter().map(Rucksack::items).collect::<Vec<_>>();
+        let mut carriers = vec![vec![]; 52];
+        for (rucksack, items) in items.iter().enumerate() {
+            for priority in priorities(*items) {
+                carriers[priority as usize - 1].push(rucksack);
+            }
+        }
+        let mut search = Self {
+            items,
+            carriers,
+            used: vec![false; data.len()],
+            left: vec![],
+            groups: vec![],
+            explored: 0,
+        };
+        search.left = (0..data.len())
+            .map(|rucksack| search.available(rucksack).count())
+            .collect();
+        search
+    }
+
+    // pairs of other unused rucksacks sharing exactly one item type with the rucksack, the
+    // badge is the only type carried by all three so every triple is listed once
+    fn available(&self, rucksack: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let free = move |other: usize| other != rucksack && !self.used[other];
+        priorities(self.items[rucksack]).flat_map(move |priority| {
+            let badge: Items = 1 << (priority - 1);
+            let carriers = &self.carriers[priority as usize - 1];
+            let pairs = carriers.iter().enumerate().filter(move |(_, j)| free(**j));
+            pairs.flat_map(move |(index, &j)| {
+                let others = self.items[rucksack] & self.items[j] & !badge;
+                carriers[index + 1..]
+                    .iter()
+                    .filter(move |k| free(**k) && self.items[**k] & others == 0)
+                    .map(move |&k| (j, k))
+            })
+        })
+    }
+
+    // triples of the rucksack are taken from (or given back to) the other rucksacks in them,
+    // members of a group are given back in the reverse order they were taken
+    fn set_used(&mut self, rucksack: usize, used: bool) {
+        if !used {
+            self.used[rucksack] = false;
+        }
+        for (j, k) in self.available(rucksack).collect::<Vec<_>>() {
+            for member in [j, k] {
+                if used {
+                    self.left[member] -= 1;
+                } else {
+                    self.left[member] += 1;
+                }
+            }
+        }
+        self.used[rucksack] = used;
+    }
+
+    fn search(&mut self) -> bool {
+        self.explored += 1;
+        let next = (0..self.used.len())
+            .filter(|rucksack| !self.used[*rucksack])
+            .min_by_key(|rucksack| self.left[*rucksack]);
+        let Some(rucksack) = next else {
+            return true;
+        };
+        for (j, k) in self.available(rucksack).collect::<Vec<_>>() {
+            for member in [rucksack, j, k] {
+                self.set_used(member, true);
+            }
+            self.groups.push([rucksack, j, k]);
+            if self.search() {
+                return true;
+            }
+            self.groups.pop();
+            for member in [k, j, rucksack] {
+                self.set_used(member, false);
+            }
+        }
+        false
+    }
+}
+
+fn discover_groups(data: &[Rucksack]) -> Grouping {
+    if !data.len().is_multiple_of(3) {
+        return Grouping::Impossible(format!(
+            "{} rucksacks cannot be split into groups of three",
+            data.len()
+        ));
+    }
+    let mut search = GroupSearch::new(data);
+    if let Some(lonely) = search.left.iter().position(|left| *left == 0) {
+        return Grouping::Impossible(format!(
+            "line {} shares exactly one item type with no pair of other rucksacks",
+            lonely + 1
+        ));
+    }
+    if !search.search() {
+        return Grouping::Impossible(format!(
+            "none of {} explored partial groupings can be completed",
+            search.explored
+        ));
+    }
+    let mut groups = search.groups;
+    for group in &mut groups {
+        group.sort_unstable();
+    }
+    groups.sort_unstable();
+    Grouping::Groups(groups)
+}
+
+// exchange of the item at position `a` of the first compartment with the item at position
+// `b` of the second one, positions in the whole line from 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Swap {
+    a: usize,
+    b: usize,
+}
+
+// fewest swaps leaving no item type in both compartments, none when every item type has to
+// stay in a single compartment and no such split fills both compartments evenly
+fn plan_reorganization(rucksack: &Rucksack) -> Option<Vec<Swap>> {
+    let contents = rucksack.contents.as_bytes();
+    let half = contents.len() / 2;
+    let mut counts = [[0; 2]; 53];
+    for (position, item) in contents.iter().enumerate() {
+        counts[char_priority(char::from(*item)) as usize][usize::from(position >= half)] += 1;
+    }
+    let types = priorities(rucksack.items()).collect::<Vec<_>>();
+
+    // kept[i][size]: most items of the first compartment staying there when the first `i`
+    // types fill `size` places of it
+    let mut kept = vec![vec![None; half + 1]; types.len() + 1];
+    kept[0][0] = Some(0);
+    for (i, priority) in types.iter().enumerate() {
+        let [in_a, in_b] = counts[*priority as usize];
+        for size in 0..=half {
+            let skip = kept[i][size];
+            let take = size
+                .checked_sub(in_a + in_b)
+                .and_then(|rest| kept[i][rest])
+                .map(|stays| stays + in_a);
+            kept[i + 1][size] = skip.max(take);
+        }
+    }
+    kept[types.len()][half]?;
+
+    // types kept in the first compartment, walking the table back
+    let mut first = 0;
+    let mut size = half;
+    for (i, priority) in types.iter().enumerate().rev() {
+        if kept[i + 1][size] != kept[i][size] {
+            let [in_a, in_b] = counts[*priority as usize];
+            first |= 1 << (priority - 1);
+            size -= in_a + in_b;
+        }
+    }
+    let in_first = |position: usize| items(&rucksack.contents[position..=position]) & first != 0;
+    let leaving = (0..half).filter(|a| !in_first(*a));
+    let arriving = (half..contents.len()).filter(|b| in_first(*b));
+    Some(leaving.zip(arriving).map(|(a, b)| Swap { a, b }).collect())
+}
+
+// groups of three found regardless of the order of lines, or why there are none, and
+// the plan to make compartments of every rucksack disjoint
+fn report_input(data_str: &str) -> Vec<utils::explain::Artifact> {
+    use utils::explain::Artifact;
+
+    let data = parse_input(data_str);
+    let mut artifacts = vec![match discover_groups(&data) {
+        Grouping::Groups(groups) => {
+            let mut artifact = Artifact::new(
+                "discovered groups",
+                &["group", "lines", "badge", "priority"],
+            );
+            let mut total = 0;
+            for (index, group) in groups.iter().enumerate() {
+                let common = group.iter().fold(Items::MAX, |common, rucksack| {
+                    common & data[*rucksack].items()
+                });
+                let priority = priorities(common).next().unwrap();
+                let mut items = data[group[0]].contents.chars();
+                let badge = items.find(|ch| char_priority(*ch) == priority).unwrap();
+                let lines = group.map(|rucksack| (rucksack + 1).to_string()).join(", ");
+                artifact.row(&[&(index + 1), &lines, &badge, &priority]);
+                total += priority;
+            }
+            artifact.row(&[&"total", &"", &"", &total]);
+            artifact
+        }
+        Grouping::Impossible(reason) => {
+            let mut artifact = Artifact::new("no grouping into badge groups", &["reason"]);
+            artifact.row(&[&reason]);
+            artifact
+        }
+    }];
+
+    let mut plan = Artifact::new("reorganization", &["line", "swaps", "plan"]);
+    let mut unfixable = Artifact::new("rucksacks that cannot be reorganized", &["line", "items"]);
+    let mut total = 0;
+    for (index, rucksack) in data.iter().enumerate() {
+        let Some(swaps) = plan_reorganization(rucksack) else {
+            unfixable.row(&[&(index + 1), &rucksack.contents]);
+            continue;
+        };
+        if swaps.is_empty() {
+            continue;
+        }
+        let item = |position: usize| char::from(rucksack.contents.as_bytes()[position]);
+        let steps = swaps
+            .iter()
+            .map(|swap| {
+                format!(
+                    "{}@{} <-> {}@{}",
+                    item(swap.a),
+                    swap.a + 1,
+                    item(swap.b),
+                    swap.b + 1
+                )
+            })
+            .collect::<Vec<_>>();
+        plan.row(&[&(index + 1), &swaps.len(), &steps.join(", ")]);
+        total += swaps.len();
+    }
+    plan.row(&[&"total", &total, &""]);
+    artifacts.extend([plan, unfixable]);
+    artifacts
+}
+
 fn parse_input(data: &str) -> Vec<Rucksack> {
     data.lines().map(Into::into).collect()
 }
 
 fn solve_part1(data: &[Rucksack]) -> Res {
-    data.iter().map(|r| char_priority(r.common_char())).sum()
+    data.iter().map(Rucksack::common_priority).sum()
 }
 
-fn solve_part2(data: &[Rucksack]) -> Res {
-    let mut priority = 0;
+fn solve_part2(data: &[Rucksack]) -> Result<Res, GroupError> {
+    let size = utils::params::get("group", GROUP_SIZE);
+    Ok(badges(data, size)?.iter().sum())
+}
 
-    for index in (0..data.len()).step_by(3) {
-        let a = &data[index];
-        let b = &data[index + 1];
-        let c = &data[index + 2];
+fn check_input(data: &str) -> Vec<String> {
+    let lines = data.lines().collect::<Vec<_>>();
+    let mut violations = utils::check::unexpected_chars(&lines, |ch| ch.is_ascii_alphabetic());
 
-        for ch in a.contents.chars() {
-            if b.contents.contains(ch) && c.contents.contains(ch) {
-                priority += char_priority(ch);
-                break;
+    for (index, line) in lines.iter().enumerate() {
+        if line.len() % 2 != 0 {
+            violations.push(format!(
+                "line {}: odd length {} cannot be split into two compartments",
+                index + 1,
+                line.len()
+            ));
+        } else if line.chars().all(|ch| ch.is_ascii_alphabetic()) {
+            let rucksack = Rucksack::from(*line);
+            let common = rucksack.intersection().count_ones();
+            if common != 1 {
+                violations.push(format!(
+                    "line {}: compartments share {common} item types instead of one",
+                    index + 1
+                ));
             }
         }
     }
-    priority
+
+    if violations.is_empty() {
+        let rucksacks = parse_input(data);
+        if let Err(error) = badges(&rucksacks, utils::params::get("group", GROUP_SIZE)) {
+            violations.push(error.to_string());
+        }
+    }
+
+    violations
 }
 
-generate_main!();
+generate_main!(check = check_input, report = report_input);
+
+generate_tests!(157, Ok(70));
+
+#[cfg(test)]
+mod badge_tests {
+    use super::*;
+
+    #[test]
+    fn masks() {
+        let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp");
+        assert_eq!(
+            priorities(rucksack.intersection()).collect::<Vec<_>>(),
+            [16]
+        );
+        assert_eq!(items("azAZ"), 1 | 1 << 25 | 1 << 26 | 1 << 51);
+    }
+
+    #[test]
+    fn group_sizes() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        assert_eq!(badges(&data, 3), Ok(vec![18, 52]));
+        assert_eq!(badges(&data[..3], 3), Ok(vec![18]));
+        assert_eq!(badges(&data[3..], 3), Ok(vec![52]));
+    }
+
+    #[test]
+    fn malformed_groups() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        let error = badges(&data[..5], 3).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "group 2 (lines 4-5) has 2 rucksacks instead of 3"
+        );
+        let error = badges(&data, 6).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "group 1 (lines 1-6) shares 0 item types instead of one"
+        );
+    }
+
+    #[test]
+    fn malformed_group_when_solving() {
+        let sample = utils::string_from_sample(DAY_ID);
+        let four_lines = sample.lines().take(4).collect::<Vec<_>>().join("\n");
+        let mut results = vec![];
+        solve_input(&four_lines, utils::runner::BOTH_PARTS, &mut |result| {
+            results.push(result);
+        });
+        assert_eq!(results[1].answer.as_deref(), Some("118"));
+        assert_eq!(results[2].answer, None);
+        assert_eq!(
+            results[2].error.as_deref(),
+            Some("group 2 (lines 4-4) has 1 rucksacks instead of 3")
+        );
+    }
+}
 
-generate_tests!(157, 70);
+#[cfg(test)]
+mod discover_tests {
+    use super::*;
+
+    // every rucksack is in a single group, sharing exactly one item type with the others
+    fn assert_partition(data: &[Rucksack], groups: &[[usize; 3]]) {
+        let mut members = groups.concat();
+        members.sort_unstable();
+        assert!(members.into_iter().eq(0..data.len()));
+        for group in groups {
+            let common = group.map(|rucksack| data[rucksack].items());
+            assert_eq!((common[0] & common[1] & common[2]).count_ones(), 1);
+        }
+    }
+
+    #[test]
+    fn shuffled_sample() {
+        let mut data = parse_input(&utils::string_from_sample(DAY_ID));
+        data.swap(1, 4);
+        data.swap(0, 5);
+        let Grouping::Groups(groups) = discover_groups(&data) else {
+            panic!("sample has a grouping");
+        };
+        // lines 1-3 are now 6, 5 and 3, lines 4-6 are 4, 2 and 1; besides the original
+        // groups, lines 6, 5 and 1 share only `J` and lines 3, 4 and 2 only `q`
+        assert!(groups == [[0, 1, 3], [2, 4, 5]] || groups == [[0, 1, 5], [2, 3, 4]]);
+        assert_partition(&data, &groups);
+    }
+
+    #[test]
+    fn impossible_groupings() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        assert_eq!(
+            discover_groups(&data[..5]),
+            Grouping::Impossible("5 rucksacks cannot be split into groups of three".to_string())
+        );
+        let data = parse_input("aa\naa\naa\nbb\nbb\ncc");
+        assert_eq!(
+            discover_groups(&data),
+            Grouping::Impossible(
+                "line 4 shares exactly one item type with no pair of other rucksacks".to_string()
+            )
+        );
+        let data = parse_input("a\nbcd\nbcd\na\ncd\nabd");
+        let Grouping::Impossible(reason) = discover_groups(&data) else {
+            panic!("no grouping exists");
+        };
+        assert!(reason.ends_with("explored partial groupings can be completed"));
+    }
+
+    // real input has 300 rucksacks and hundreds of thousands of triples sharing one type
+    #[test]
+    fn shuffled_input() {
+        let mut data = parse_input(&utils::string_from_input(DAY_ID));
+        assert_eq!(data.len(), 300);
+        let mut rng = utils::differential::Rng::new(3);
+        for index in (1..data.len()).rev() {
+            data.swap(index, rng.index(0..=index));
+        }
+        let Grouping::Groups(groups) = discover_groups(&data) else {
+            panic!("input has a grouping");
+        };
+        assert_partition(&data, &groups);
+    }
+}
+
+#[cfg(test)]
+mod reorganization_tests {
+    use super::*;
+
+    fn reorganized(line: &str) -> Option<String> {
+        let swaps = plan_reorganization(&Rucksack::from(line))?;
+        let mut items = line.as_bytes().to_vec();
+        for swap in swaps {
+            items.swap(swap.a, swap.b);
+        }
+        Some(String::from_utf8(items).unwrap())
+    }
+
+    #[test]
+    fn fewest_swaps() {
+        // `p` moves to the first compartment in exchange for an item of type stored once
+        let line = "vJrwpWtwJgWrhcsFMMfFFhFp";
+        let swaps = plan_reorganization(&Rucksack::from(line)).unwrap();
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(
+            Rucksack::from(reorganized(line).unwrap().as_str()).intersection(),
+            0
+        );
+        // `b` leaving for `a` beats `a` and `c` leaving for `b` and `d`
+        let swaps = plan_reorganization(&Rucksack::from("aabcabdd")).unwrap();
+        assert_eq!(swaps, [Swap { a: 2, b: 4 }]);
+        assert_eq!(reorganized("aabcabdd").as_deref(), Some("aaacbbdd"));
+    }
+
+    #[test]
+    fn unfixable() {
+        assert_eq!(plan_reorganization(&Rucksack::from("aaab")), None);
+        assert_eq!(plan_reorganization(&Rucksack::from("abcd")), Some(vec![]));
+        let artifacts = report_input("aaab\nabcd\nabca");
+        assert_eq!(artifacts[1].column("line"), ["3", "total"]);
+        assert_eq!(artifacts[2].column("line"), ["1"]);
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_valid() {
+        assert!(check_input(&utils::string_from_sample(DAY_ID)).is_empty());
+    }
+
+    #[test]
+    fn violations() {
+        assert_eq!(
+            check_input("vJrwpWtwJgWrhcsFMMfFFhFp\nabc\nab1b"),
+            [
+                "line 3, column 3: unexpected `1`",
+                "line 2: odd length 3 cannot be split into two compartments",
+            ]
+        );
+        assert_eq!(
+            check_input("abcd\nabca\nadcd"),
+            ["line 1: compartments share 0 item types instead of one"]
+        );
+        let sample = utils::string_from_sample(DAY_ID);
+        let four_lines = sample.lines().take(4).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            check_input(&four_lines),
+            ["group 2 (lines 4-4) has 1 rucksacks instead of 3"]
+        );
+    }
+}
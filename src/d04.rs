@@ -1,39 +1,36 @@
 mod utils;
-const DAY_ID: utils::DayIdType = 4;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 4);
 
 type Res = usize;
 
+use utils::interval::Interval;
+
 type Number = u32;
-type Assignment = [Number; 4];
+type Assignment = [Interval; 2];
 
 fn parse_input(data: &str) -> Vec<Assignment> {
     data.lines()
         .map(|x| {
             let elves: Vec<&str> = x.split(',').collect();
-            let a: Vec<Number> = elves[0]
-                .split('-')
-                .map(|x| x.parse::<u32>().unwrap())
-                .collect();
-            let b: Vec<Number> = elves[1]
-                .split('-')
-                .map(|x| x.parse::<u32>().unwrap())
-                .collect();
-            [a[0], a[1], b[0], b[1]]
+            [elves[0], elves[1]].map(|elf| {
+                let sections: Vec<Number> = elf
+                    .split('-')
+                    .map(|x| x.parse::<Number>().unwrap())
+                    .collect();
+                Interval::new(sections[0].into(), sections[1].into())
+            })
         })
         .collect()
 }
 
 pub fn has_complete_overlap(assignment: &Assignment) -> bool {
-    let [a1, a2, b1, b2] = assignment;
-    (a1 >= b1 && a2 <= b2) || (b1 >= a1 && b2 <= a2)
+    let [a, b] = assignment;
+    a.contains_interval(b) || b.contains_interval(a)
 }
 
 pub fn has_some_overlap(assignment: &Assignment) -> bool {
-    let [a1, a2, b1, b2] = assignment;
-    (a1 >= b1 && a1 <= b2)
-        || (a2 >= b1 && a2 <= b2)
-        || (b1 >= a1 && b1 <= a2)
-        || (b2 >= a1 && b2 <= a2)
+    let [a, b] = assignment;
+    a.overlaps(b)
 }
 
 fn solve_part1(data: &[Assignment]) -> Res {
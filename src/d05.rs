@@ -1,6 +1,6 @@
 #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 5;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 5);
 
 type Res = String;
 
@@ -42,24 +42,98 @@ impl Towers {
     pub fn top_crates(&self) -> String {
         self.towers.iter().map(|t| t[t.len() - 1]).collect()
     }
+
+    // drawing in the same format as puzzle input
+    pub fn render(&self) -> String {
+        let height = self.towers.iter().map(Vec::len).max().unwrap_or_default();
+        let mut lines = vec![];
+        for level in (0..height).rev() {
+            let line = self
+                .towers
+                .iter()
+                .map(|tower| match tower.get(level) {
+                    Some(letter) => format!("[{letter}]"),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            lines.push(line.trim_end().to_string());
+        }
+        let numbers = (1..=self.towers.len())
+            .map(|index| format!(" {index} "))
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(numbers.trim_end().to_string());
+        lines.join("\n")
+    }
+}
+
+// crane moves applied one at a time, yields every performed move
+struct CraneSteps<'a> {
+    towers: Towers,
+    moves: std::slice::Iter<'a, Move>,
+    moves_done: usize,
+    moves_at_once: bool,
+}
+
+impl<'a> CraneSteps<'a> {
+    pub fn new(towers: &Towers, moves: &'a [Move], moves_at_once: bool) -> Self {
+        Self {
+            towers: towers.clone(),
+            moves: moves.iter(),
+            moves_done: 0,
+            moves_at_once,
+        }
+    }
+}
+
+impl Iterator for CraneSteps<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.moves.next()?;
+        if self.moves_at_once {
+            self.towers.process_move_new(m);
+        } else {
+            self.towers.process_move(m);
+        }
+        self.moves_done += 1;
+        Some(m.clone())
+    }
+}
+
+impl utils::step::Inspect for CraneSteps<'_> {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        let highest = self.towers.towers.iter().map(Vec::len).max();
+        vec![
+            utils::step::value("moves", self.moves_done),
+            utils::step::value("highest", highest.unwrap_or_default()),
+        ]
+    }
+
+    fn render(&self) -> String {
+        self.towers.render()
+    }
 }
 
 fn parse_input(data: &str) -> (Towers, Vec<Move>) {
-    let (tower_text, moves_text) = data.split_once("\n\n").unwrap();
+    let blocks = utils::text::blocks(data);
+    let (tower_lines, move_lines) = (&blocks[0], &blocks[1]);
 
-    let tower_count = (tower_text.lines().next().unwrap().len() + 1) / 4;
+    // last tower line numbers the towers, lines above may miss trailing empty cells
+    let tower_count = tower_lines.last().unwrap().split_ascii_whitespace().count();
     let mut towers = vec![Vec::new(); tower_count];
 
-    for line in tower_text.lines() {
+    for line in tower_lines {
         // init state section stops on first line with no crate displayed [.]
         if !line.contains('[') {
             break;
         }
         let chars: Vec<char> = line.chars().collect();
-        for index in 0..tower_count {
-            let letter = chars[index * 4 + 1];
+        for (index, tower) in towers.iter_mut().enumerate() {
+            let letter = chars.get(index * 4 + 1).copied().unwrap_or(' ');
             if letter.is_ascii_alphabetic() {
-                towers[index].push(letter);
+                tower.push(letter);
             }
         }
     }
@@ -70,8 +144,8 @@ fn parse_input(data: &str) -> (Towers, Vec<Move>) {
 
     // let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
 
-    let moves = moves_text
-        .lines()
+    let moves = move_lines
+        .iter()
         .map(|line| {
             let values: Vec<usize> = line
                 .split(' ')
@@ -95,22 +169,214 @@ fn parse_input(data: &str) -> (Towers, Vec<Move>) {
 }
 
 fn solve_part1((towers, moves): &(Towers, Vec<Move>)) -> Res {
-    let mut my_towers = towers.clone();
-    for m in moves {
-        my_towers.process_move(m);
-    }
-
-    my_towers.top_crates()
+    let mut steps = CraneSteps::new(towers, moves, false);
+    steps.by_ref().for_each(drop);
+    steps.towers.top_crates()
 }
 
 fn solve_part2((towers, moves): &(Towers, Vec<Move>)) -> Res {
-    let mut my_towers = towers.clone();
-    for m in moves {
-        my_towers.process_move_new(m);
+    let mut steps = CraneSteps::new(towers, moves, true);
+    steps.by_ref().for_each(drop);
+    steps.towers.top_crates()
+}
+
+fn step_through(data_str: &str, part: u8) {
+    let (towers, moves) = parse_input(data_str);
+    utils::step::prompt(CraneSteps::new(&towers, &moves, part == 2));
+}
+
+// checks crate cells of a single tower line, updates tower heights
+fn check_tower_line(line_no: usize, line: &str, heights: &mut [usize]) -> Vec<String> {
+    let mut violations = vec![];
+    let chars = line.chars().collect::<Vec<_>>();
+    for (index, height) in heights.iter_mut().enumerate() {
+        let cell = chars.iter().skip(index * 4).take(3).collect::<String>();
+        let column = index * 4 + 1;
+        if cell.trim().is_empty() {
+            if *height > 0 {
+                violations.push(format!(
+                    "line {line_no}, column {column}: crate of tower {} floats above empty space",
+                    index + 1
+                ));
+            }
+        } else if cell.len() == 3
+            && cell.starts_with('[')
+            && cell.ends_with(']')
+            && chars[index * 4 + 1].is_ascii_uppercase()
+        {
+            *height += 1;
+        } else {
+            violations.push(format!(
+                "line {line_no}, column {column}: `{cell}` is not aligned crate `[X]`"
+            ));
+        }
+        if chars.get(index * 4 + 3).is_some_and(|ch| *ch != ' ') {
+            violations.push(format!(
+                "line {line_no}, column {}: towers are not separated by space",
+                column + 3
+            ));
+        }
+    }
+    violations
+}
+
+fn check_input(data: &str) -> Vec<String> {
+    let Some((tower_text, moves_text)) = data.split_once("\n\n") else {
+        return vec!["missing blank line between towers and moves".to_string()];
+    };
+    let tower_lines = tower_text.lines().collect::<Vec<_>>();
+    let Some((numbers_line, crate_lines)) = tower_lines.split_last() else {
+        return vec!["missing towers".to_string()];
+    };
+
+    let mut violations = vec![];
+
+    // trailing whitespace is not kept in normalized input, lines can end with last crate
+    let tower_count = numbers_line.len().div_ceil(4);
+    let numbers = numbers_line.split_ascii_whitespace().collect::<Vec<_>>();
+    let expected_numbers = (1..=tower_count).map(|n| n.to_string()).collect::<Vec<_>>();
+    if numbers != expected_numbers {
+        violations.push(format!(
+            "line {}: tower numbers `{}` are not 1 to {tower_count}",
+            tower_lines.len(),
+            numbers.join(" ")
+        ));
+    }
+    for (index, number) in numbers.iter().enumerate() {
+        if numbers_line.find(number) != Some(index * 4 + 1) {
+            violations.push(format!(
+                "line {}: tower number {number} is not aligned with its column",
+                tower_lines.len()
+            ));
+        }
+    }
+
+    // crate lines go from the top - empty cell below a crate already seen means floating crate
+    let mut heights = vec![0; tower_count];
+    for (index, line) in crate_lines.iter().enumerate() {
+        violations.extend(check_tower_line(index + 1, line, &mut heights));
     }
-    my_towers.top_crates()
+
+    let first_move_line = tower_lines.len() + 2;
+    for (index, line) in moves_text.lines().enumerate() {
+        let line_no = first_move_line + index;
+        let words = line.split(' ').collect::<Vec<_>>();
+        let ["move", count, "from", from, "to", to] = words[..] else {
+            violations.push(format!(
+                "line {line_no}: `{line}` is not `move <count> from <tower> to <tower>`"
+            ));
+            continue;
+        };
+        let (Ok(count), Ok(from), Ok(to)) = (
+            count.parse::<usize>(),
+            from.parse::<usize>(),
+            to.parse::<usize>(),
+        ) else {
+            violations.push(format!("line {line_no}: `{line}` contains invalid number"));
+            continue;
+        };
+        if from == 0 || from > tower_count || to == 0 || to > tower_count {
+            violations.push(format!(
+                "line {line_no}: tower out of range 1 to {tower_count}"
+            ));
+            continue;
+        }
+        if count > heights[from - 1] {
+            violations.push(format!(
+                "line {line_no}: moving {count} crates from tower {from} holding only {}",
+                heights[from - 1]
+            ));
+            continue;
+        }
+        heights[from - 1] -= count;
+        heights[to - 1] += count;
+    }
+
+    for (index, height) in heights.iter().enumerate() {
+        if *height == 0 {
+            violations.push(format!("tower {} is empty after all moves", index + 1));
+        }
+    }
+
+    violations
 }
 
-generate_main!();
+generate_main!(step = step_through, check = check_input);
 
 generate_tests!("CMZ".to_string(), "MCD".to_string());
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    #[test]
+    fn towers_after_each_part() {
+        let (towers, moves) = parse_input(&utils::string_from_sample(DAY_ID));
+        for (part, moves_at_once) in [(1, false), (2, true)] {
+            let mut steps = CraneSteps::new(&towers, &moves, moves_at_once);
+            steps.by_ref().for_each(drop);
+            utils::snapshot::assert_snapshot(
+                DAY_ID,
+                &format!("towers-part{part}"),
+                &steps.towers.render(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let (towers, moves) = parse_input(&utils::string_from_sample(DAY_ID));
+        let output = utils::step::run_script(
+            CraneSteps::new(&towers, &moves, false),
+            &["n 2", "until moves >= 4"],
+        );
+        assert!(output.contains("moves=4, highest=4"), "{output}");
+        let output = utils::step::run_script(CraneSteps::new(&towers, &moves, true), &["n 10"]);
+        assert!(
+            output.contains("simulation finished after 4 steps"),
+            "{output}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_valid() {
+        assert!(check_input(&utils::string_from_sample(DAY_ID)).is_empty());
+    }
+
+    #[test]
+    fn violations() {
+        let sample = utils::string_from_sample(DAY_ID);
+        let misaligned = sample.replace(" 1   2   3", "1   2   3");
+        assert!(check_input(&misaligned)
+            .contains(&"line 4: tower number 1 is not aligned with its column".to_string()));
+        let out_of_range = sample.replace("move 1 from 2 to 1", "move 1 from 4 to 1");
+        assert_eq!(
+            check_input(&out_of_range)[0],
+            "line 6: tower out of range 1 to 3"
+        );
+        let too_many = sample.replace("move 1 from 2 to 1", "move 5 from 2 to 1");
+        assert_eq!(
+            check_input(&too_many)[0],
+            "line 6: moving 5 crates from tower 2 holding only 3"
+        );
+        let invalid = sample.replace("move 1 from 2 to 1", "move one from 2 to 1");
+        assert_eq!(
+            check_input(&invalid)[0],
+            "line 6: `move one from 2 to 1` contains invalid number"
+        );
+        assert_eq!(
+            check_input("1\nmove 1 from 1 to 1"),
+            ["missing blank line between towers and moves"]
+        );
+    }
+}
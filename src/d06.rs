@@ -1,8 +1,9 @@
 #![warn(clippy::pedantic)]
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::io::{self, BufRead};
 
 mod utils;
-const DAY_ID: utils::DayIdType = 6;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 6);
 
 type Res = usize;
 
@@ -39,6 +40,132 @@ fn solve_part2(data: &[char]) -> Res {
     index_of_unique_chain(data, 14)
 }
 
-generate_main!();
+// recognizes the first chain of distinct characters in a stream of characters
+struct MarkerDetector {
+    unique_length: usize,
+    recent: VecDeque<u8>,
+    position: usize,
+    found: Option<usize>,
+}
+
+impl MarkerDetector {
+    pub fn new(unique_length: usize) -> Self {
+        Self {
+            unique_length,
+            recent: VecDeque::with_capacity(unique_length + 1),
+            position: 0,
+            found: None,
+        }
+    }
+
+    // returns position right after the chain when the chain gets completed by given byte
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        if self.found.is_some() {
+            return None;
+        }
+        self.position += 1;
+        self.recent.push_back(byte);
+        if self.recent.len() > self.unique_length {
+            self.recent.pop_front();
+        }
+        let distinct = self.recent.iter().collect::<HashSet<_>>().len();
+        if distinct == self.unique_length {
+            self.found = Some(self.position);
+        }
+        self.found
+    }
+}
+
+// bytes are read only until both markers are found, nothing is parsed upfront so there is
+// no parse stage and both parts are timed from the first byte read
+fn stream_input(
+    reader: &mut dyn BufRead,
+    report: &mut dyn FnMut(utils::StageResult),
+) -> io::Result<()> {
+    let mut detectors = [MarkerDetector::new(4), MarkerDetector::new(14)];
+    let start = std::time::Instant::now();
+
+    for byte in utils::stream::bytes(reader) {
+        let byte = byte?;
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+        for (part, detector) in (1..).zip(&mut detectors) {
+            if let Some(position) = detector.push(byte) {
+                report(utils::StageResult::new(
+                    utils::Stage::Part(part),
+                    Some(position.to_string()),
+                    start.elapsed(),
+                ));
+            }
+        }
+        if detectors.iter().all(|detector| detector.found.is_some()) {
+            break;
+        }
+    }
+
+    for (part, detector) in (1..).zip(&detectors) {
+        if detector.found.is_none() {
+            report(utils::StageResult {
+                error: Some(format!(
+                    "no {} distinct characters in a row",
+                    detector.unique_length
+                )),
+                ..utils::StageResult::new(utils::Stage::Part(part), None, start.elapsed())
+            });
+        }
+    }
+
+    Ok(())
+}
+
+generate_main!(stream = stream_input);
 
 generate_tests!(7, 19);
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    #[test]
+    fn stream_variants() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::text::assert_same_on_variants(&data_str, |data| {
+            utils::stream::answers(stream_input, data).unwrap()
+        });
+    }
+
+    #[test]
+    fn stream_test() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let mut answers = vec![];
+        let mut stages = vec![];
+        stream_input(&mut data_str.as_bytes(), &mut |result| {
+            stages.push(result.stage);
+            answers.extend(result.answer);
+        })
+        .unwrap();
+        assert_eq!(answers, ["7", "19"]);
+        assert_eq!(stages, [utils::Stage::Part(1), utils::Stage::Part(2)]);
+    }
+
+    #[test]
+    fn stream_without_marker() {
+        let mut results = vec![];
+        stream_input(&mut "abcdabcd".as_bytes(), &mut |result| {
+            results.push((result.stage, result.answer, result.error));
+        })
+        .unwrap();
+        assert_eq!(
+            results,
+            [
+                (utils::Stage::Part(1), Some("4".to_string()), None),
+                (
+                    utils::Stage::Part(2),
+                    None,
+                    Some("no 14 distinct characters in a row".to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 7;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 7);
 
 type Res = usize;
 
@@ -44,17 +44,24 @@ fn parse_input(data: &str) -> Data {
     map
 }
 
+const SMALL_DIR_LIMIT: usize = 100_000;
+const DISK_SPACE: usize = 70_000_000;
+const UNUSED_SPACE: usize = 30_000_000;
+
 fn solve_part1(data: &Data) -> Res {
-    data.values().filter(|size| **size < 100_000).sum()
+    data.values().filter(|size| **size < SMALL_DIR_LIMIT).sum()
 }
 
-fn solve_part2(data: &Data) -> Res {
-    const DISK_SPACE: usize = 70_000_000;
-    const UNUSED_SPACE: usize = 30_000_000;
+// space taken above what leaves enough unused space for the update
+fn need_to_delete(data: &Data) -> Res {
     const MAX_TAKEN: usize = DISK_SPACE - UNUSED_SPACE;
 
     let root_dir_size = data.values().max().unwrap();
-    let need_to_delete = root_dir_size - MAX_TAKEN;
+    root_dir_size - MAX_TAKEN
+}
+
+fn solve_part2(data: &Data) -> Res {
+    let need_to_delete = need_to_delete(data);
 
     data.values()
         .filter(|v| **v >= need_to_delete)
@@ -63,6 +70,82 @@ fn solve_part2(data: &Data) -> Res {
         .unwrap()
 }
 
-generate_main!();
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let data = parse_input(data_str);
+    let mut dirs = data.iter().collect::<Vec<_>>();
+    dirs.sort_by_key(|(dir, size)| (**size, *dir));
+
+    if part == 1 {
+        let mut small = utils::explain::Artifact::new(
+            format!("directories smaller than {SMALL_DIR_LIMIT}"),
+            &["directory", "size"],
+        );
+        for (dir, size) in dirs.iter().filter(|(_, size)| **size < SMALL_DIR_LIMIT) {
+            small.row(&[dir, size]);
+        }
+        return vec![small];
+    }
+
+    let root_dir_size = data.values().max().unwrap();
+    let need_to_delete = need_to_delete(&data);
+    let mut space = utils::explain::Artifact::new("disk space", &["taken", "unused", "to free"]);
+    space.row(&[
+        root_dir_size,
+        &(DISK_SPACE - root_dir_size),
+        &need_to_delete,
+    ]);
+
+    let mut deleted = utils::explain::Artifact::new("deleted directory", &["directory", "size"]);
+    let (dir, size) = dirs
+        .iter()
+        .find(|(_, size)| **size >= need_to_delete)
+        .unwrap();
+    deleted.row(&[dir, size]);
+
+    vec![space, deleted]
+}
+
+generate_main!(explain = explain_input);
 
 generate_tests!(95437, 24_933_642);
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn explains_deleted_directory() {
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 2);
+        assert_eq!(artifacts[1].column("directory"), ["/d"]);
+        assert_eq!(artifacts[1].column("size"), ["24933642"]);
+    }
+
+    #[test]
+    fn serves_explanation_as_json() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let day = utils::Day {
+            explain: Some(explain_input),
+            ..utils::Day::new(DAY_ID, false, solve_input)
+        };
+        let registry = utils::server::Registry::new(day);
+        std::thread::spawn(move || utils::server::serve(&registry, &listener));
+
+        let sample = utils::string_from_sample(DAY_ID);
+        let mut stream = std::net::TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /day/7/part/2/explain HTTP/1.1\r\nContent-Length: {}\r\n\r\n{sample}",
+            sample.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(
+            "{\"title\":\"deleted directory\",\"rows\":[{\"directory\":\"/d\",\"size\":\"24933642\"}]}"
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 8;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 8);
 
 type Res = usize;
 
@@ -49,7 +49,8 @@ fn parse_input(data: &str) -> Trees {
     trees
 }
 
-fn solve_part1(trees: &Trees) -> Res {
+// reference solution - scans all the trees in every direction from each tree
+fn count_visible_reference(trees: &Trees) -> Res {
     let size = trees.size;
 
     let mut visible: SquareVec<bool> = SquareVec::new(size, false);
@@ -108,6 +109,36 @@ fn solve_part1(trees: &Trees) -> Res {
     visible.vec.iter().filter(|x| **x).count()
 }
 
+// single pass from each side, tree is visible if taller than all the trees passed before
+fn count_visible(trees: &Trees) -> Res {
+    let size = trees.size;
+
+    let mut visible: SquareVec<bool> = SquareVec::new(size, false);
+    let mut mark_line = |coords: &mut dyn Iterator<Item = (usize, usize)>| {
+        let mut tallest = None;
+        for (row, col) in coords {
+            let height = trees.get(row, col);
+            if tallest.is_none_or(|tallest| height > tallest) {
+                visible.set(row, col, true);
+                tallest = Some(height);
+            }
+        }
+    };
+
+    for i in 0..size {
+        mark_line(&mut (0..size).map(|col| (i, col)));
+        mark_line(&mut (0..size).rev().map(|col| (i, col)));
+        mark_line(&mut (0..size).map(|row| (row, i)));
+        mark_line(&mut (0..size).rev().map(|row| (row, i)));
+    }
+
+    visible.vec.iter().filter(|x| **x).count()
+}
+
+fn solve_part1(trees: &Trees) -> Res {
+    count_visible(trees)
+}
+
 fn solve_part2(trees: &Trees) -> Res {
     let size = trees.size;
 
@@ -159,7 +190,7 @@ fn solve_part2(trees: &Trees) -> Res {
                 down += 1;
             }
 
-            // println!("[{row};{col}]: {left}, {right}, {top}, {down}");
+            trace!("[{row};{col}]: {left}, {right}, {top}, {down}");
             let product = left * right * top * down;
             if product > scenic_score {
                 scenic_score = product;
@@ -170,6 +201,36 @@ fn solve_part2(trees: &Trees) -> Res {
     scenic_score
 }
 
-generate_main!();
+fn differential(cases: usize, seed: u64) -> Option<utils::differential::Divergence> {
+    utils::differential::first_divergence(
+        cases,
+        seed,
+        |rng| {
+            let size = rng.index(1..=12);
+            (0..size)
+                .map(|_| {
+                    (0..size)
+                        .map(|_| char::from(b'0' + u8::try_from(rng.index(0..=9)).unwrap()))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        |input| count_visible_reference(&parse_input(input)),
+        |input| count_visible(&parse_input(input)),
+    )
+}
+
+generate_main!(differential = differential);
 
 generate_tests!(21, 8);
+
+#[cfg(test)]
+mod differential_tests {
+    #[test]
+    fn optimized_matches_reference() {
+        if let Some(divergence) = super::differential(500, 8) {
+            panic!("{divergence}");
+        }
+    }
+}
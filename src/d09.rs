@@ -2,7 +2,7 @@
 use std::collections::HashSet;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 9;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 9);
 
 type Res = usize;
 
@@ -62,54 +62,145 @@ impl Position {
     }
 }
 
-fn solve_part1(data: &[Step]) -> Res {
-    let mut head = Position { x: 0, y: 0 };
-    let mut tail = Position { x: 0, y: 0 };
-
-    let mut tail_positions: HashSet<Position> = HashSet::new();
-
-    for (direction, size) in data.iter() {
-        for _ in 0..*size {
-            let last_head = head;
-            head.step_once(*direction);
+// rope moved by single unit step of its head at a time, yields position of the head
+struct RopeSteps<'a> {
+    knots: Vec<Position>,
+    steps: std::slice::Iter<'a, Step>,
+    current: Option<Step>,
+    steps_done: usize,
+    tail_positions: HashSet<Position>,
+}
 
-            if head.distance(tail) > 1 {
-                tail = last_head;
-            }
-            tail_positions.insert(tail);
+impl<'a> RopeSteps<'a> {
+    pub fn new(data: &'a [Step], knot_count: usize) -> Self {
+        let start = Position { x: 0, y: 0 };
+        Self {
+            knots: vec![start; knot_count],
+            steps: data.iter(),
+            current: None,
+            steps_done: 0,
+            tail_positions: HashSet::from([start]),
         }
     }
 
-    tail_positions.len()
+    pub fn head(&self) -> Position {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Position {
+        self.knots[self.knots.len() - 1]
+    }
 }
 
-fn solve_part2(data: &[Step]) -> Res {
-    let mut knots = vec![Position { x: 0, y: 0 }; 10];
+impl Iterator for RopeSteps<'_> {
+    type Item = Position;
 
-    let mut tail_positions: HashSet<Position> = HashSet::new();
+    fn next(&mut self) -> Option<Self::Item> {
+        let (direction, size) = match self.current {
+            Some((direction, size)) if size > 0 => (direction, size),
+            _ => *self.steps.find(|(_, size)| *size > 0)?,
+        };
+        self.current = Some((direction, size - 1));
 
-    for (direction, size) in data.iter() {
-        for _ in 0..*size {
-            knots[0].step_once(*direction);
+        self.knots[0].step_once(direction);
 
-            for i in 1..knots.len() {
-                let head = knots[i - 1];
-                let tail = &mut knots[i];
+        for i in 1..self.knots.len() {
+            let head = self.knots[i - 1];
+            let tail = &mut self.knots[i];
 
-                if head.distance(*tail) > 1 {
-                    tail.x += (head.x - tail.x).signum();
-                    tail.y += (head.y - tail.y).signum();
-                } else {
-                    break;
-                }
+            if head.distance(*tail) > 1 {
+                tail.x += (head.x - tail.x).signum();
+                tail.y += (head.y - tail.y).signum();
+            } else {
+                break;
             }
-            tail_positions.insert(knots[9]);
         }
+        self.tail_positions.insert(self.tail());
+        self.steps_done += 1;
+
+        Some(self.head())
     }
+}
 
-    tail_positions.len()
+impl utils::step::Inspect for RopeSteps<'_> {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            utils::step::value("steps", self.steps_done),
+            utils::step::value("head_x", self.head().x),
+            utils::step::value("head_y", self.head().y),
+            utils::step::value("tail_x", self.tail().x),
+            utils::step::value("tail_y", self.tail().y),
+            utils::step::value("visited", self.tail_positions.len()),
+        ]
+    }
+
+    // knots (H, 1, 2...) over positions visited by tail (#), start is marked with s
+    fn render(&self) -> String {
+        let all = || self.knots.iter().chain(&self.tail_positions);
+        let min_x = all().map(|p| p.x).min().unwrap();
+        let max_x = all().map(|p| p.x).max().unwrap();
+        let min_y = all().map(|p| p.y).min().unwrap();
+        let max_y = all().map(|p| p.y).max().unwrap();
+
+        let mut picture = String::new();
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let position = Position { x, y };
+                let ch = match self.knots.iter().position(|knot| *knot == position) {
+                    Some(0) => 'H',
+                    Some(index) => {
+                        char::from_digit(u32::try_from(index % 10).unwrap(), 10).unwrap()
+                    }
+                    None if x == 0 && y == 0 => 's',
+                    None if self.tail_positions.contains(&position) => '#',
+                    None => '.',
+                };
+                picture.push(ch);
+            }
+            picture.push('\n');
+        }
+        picture
+    }
 }
 
-generate_main!();
+fn solve_part1(data: &[Step]) -> Res {
+    let mut rope = RopeSteps::new(data, 2);
+    rope.by_ref().for_each(drop);
+    rope.tail_positions.len()
+}
+
+fn solve_part2(data: &[Step]) -> Res {
+    let mut rope = RopeSteps::new(data, 10);
+    rope.by_ref().for_each(drop);
+    rope.tail_positions.len()
+}
+
+fn step_through(data_str: &str, part: u8) {
+    let data = parse_input(data_str);
+    let knot_count = if part == 2 { 10 } else { 2 };
+    utils::step::prompt(RopeSteps::new(&data, knot_count));
+}
+
+generate_main!(step = step_through);
 
 generate_tests!(13, 1);
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        let output = utils::step::run_script(RopeSteps::new(&data, 2), &["n 100"]);
+        assert!(
+            output.contains("steps=24, head_x=2, head_y=-2, tail_x=1, tail_y=-2, visited=13"),
+            "{output}"
+        );
+        let output = utils::step::run_script(RopeSteps::new(&data, 10), &["until steps == 24"]);
+        assert!(
+            output.contains("steps=24, head_x=2, head_y=-2, tail_x=0, tail_y=0, visited=1"),
+            "{output}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 // #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 10;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 10);
 
 type Res = i32;
 
@@ -36,90 +36,172 @@ fn parse_input(data: &str) -> Vec<Command> {
     data.lines().map(Into::into).collect()
 }
 
-fn solve_part1(data: &[Command]) -> Res {
-    let mut x = 1;
-
-    let mut signal = 0;
-
-    let mut cycles = 0;
+const COLS: usize = 40;
+const ROWS: usize = 6;
 
-    let mut last_was_add = false;
-
-    let mut checked = 0; // compensate for some bug mis-indexing last item in do_check
+#[derive(Debug, Copy, Clone)]
+struct CycleState {
+    pub cycle: Res,
+    pub x: Res,
+    #[allow(dead_code)] // shown in step-through output only
+    pub command: Command,
+}
 
-    for (step, command) in data.iter().enumerate() {
-        let do_check = CYCLES_TO_CHECK.contains(&cycles)
-            || (last_was_add && CYCLES_TO_CHECK.contains(&(cycles - 1)));
+// CPU clock ticking one cycle at a time, yields register state during each cycle
+struct CpuCycles<'a> {
+    commands: std::slice::Iter<'a, Command>,
+    in_progress: Option<(Res, Command)>,
+    x: Res,
+    x_during: Option<Res>,
+    cycle: Res,
+    // commands finished so far
+    step: usize,
+    screen: [[char; COLS]; ROWS],
+}
 
-        if do_check {
-            let correct_cycle = CYCLES_TO_CHECK[checked];
-            let update = if let Command::AddX(fix) = &data[step - 1] {
-                (x - *fix) * correct_cycle
-            } else {
-                x * correct_cycle
-            };
-            signal += update;
-            checked += 1;
+impl<'a> CpuCycles<'a> {
+    pub fn new(data: &'a [Command]) -> Self {
+        Self {
+            commands: data.iter(),
+            in_progress: None,
+            x: 1,
+            x_during: None,
+            cycle: 0,
+            step: 0,
+            screen: [['.'; COLS]; ROWS],
         }
-        cycles += command.cycles();
-        match command {
-            Command::AddX(num) => {
-                x += num;
-                last_was_add = true;
-            }
-            Command::Noop => {
-                last_was_add = false;
-            }
+    }
+
+    fn draw_pixel(&mut self) {
+        let position = usize::try_from(self.cycle - 1).unwrap();
+        let (row, col) = (position / COLS, position % COLS);
+        if row < ROWS && (self.x - 1..=self.x + 1).contains(&Res::try_from(col).unwrap()) {
+            self.screen[row][col] = '#';
         }
     }
 
-    signal
+    pub fn screen_rows(&self) -> Vec<String> {
+        self.screen.iter().map(|row| row.iter().collect()).collect()
+    }
 }
 
-fn solve_part2(data: &[Command]) -> Res {
-    const COLS: i32 = 40;
-    const ROWS: i32 = 6;
-    let mut screen = [['.'; COLS as usize]; ROWS as usize];
-
-    for cycle in 0i32..(ROWS * COLS) {
-        let row = cycle / 40;
-        let col = cycle % 40;
-
-        let mut cursor = 1;
-        let mut cycles_needed = 0;
-        let mut prev_add;
-        for command in data.iter() {
-            match command {
-                Command::AddX(num) => {
-                    cursor += num;
-                    prev_add = Some(num);
-                }
-                Command::Noop => {
-                    prev_add = None;
-                }
+impl Iterator for CpuCycles<'_> {
+    type Item = CycleState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cycles_left, command) = match self.in_progress {
+            Some(in_progress) => in_progress,
+            None => {
+                let command = *self.commands.next()?;
+                (command.cycles(), command)
             }
-            cycles_needed += command.cycles();
-            if cycles_needed > cycle {
-                if let Some(num) = prev_add {
-                    cursor -= num;
-                }
-                break;
+        };
+
+        self.cycle += 1;
+        let state = CycleState {
+            cycle: self.cycle,
+            x: self.x,
+            command,
+        };
+        self.x_during = Some(self.x);
+        self.draw_pixel();
+
+        if cycles_left > 1 {
+            self.in_progress = Some((cycles_left - 1, command));
+        } else {
+            trace!(
+                "step {}: {command:?}, cycles {}, x {}",
+                self.step,
+                self.cycle,
+                self.x
+            );
+            self.step += 1;
+            if let Command::AddX(num) = command {
+                self.x += num;
             }
+            self.in_progress = None;
         }
 
-        if [cursor - 1, cursor, cursor + 1].contains(&(col as Res)) {
-            screen[row as usize][col as usize] = '#';
-        }
+        Some(state)
     }
+}
 
-    for row in screen {
-        let row_str: String = row.iter().collect();
-        println!("\t{}", row_str);
+impl utils::step::Inspect for CpuCycles<'_> {
+    // register value during the last cycle, not the one updated at its end
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        let x = self.x_during.unwrap_or(self.x);
+        vec![
+            utils::step::value("cycle", self.cycle),
+            utils::step::value("x", x),
+            utils::step::value("signal", self.cycle * x),
+        ]
     }
 
-    0
+    fn render(&self) -> String {
+        self.screen_rows().join("\n")
+    }
 }
 
-generate_main!();
+fn solve_part1(data: &[Command]) -> Res {
+    CpuCycles::new(data)
+        .filter(|state| CYCLES_TO_CHECK.contains(&state.cycle))
+        .fold(0, |signal, state| {
+            let update = state.cycle * state.x;
+            let signal = signal + update;
+            verbose!(
+                "cycle {}: signal strength {update}, total {signal}",
+                state.cycle
+            );
+            signal
+        })
+}
+
+// the picture drawn on the screen, its letters are read by the user
+fn solve_part2(data: &[Command]) -> String {
+    let mut cpu = CpuCycles::new(data);
+    cpu.by_ref().for_each(drop);
+    cpu.screen_rows().join("\n")
+}
+
+fn step_through(data_str: &str, _part: u8) {
+    let data = parse_input(data_str);
+    utils::step::prompt(CpuCycles::new(&data));
+}
+
+generate_main!(step = step_through);
+
+generate_tests!(
+    13140,
+    include_str!("../snapshots/2022/10-crt-sample.txt").trim_end()
+);
 
-generate_tests!(13140, 0);
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    fn screen(data_str: &str) -> String {
+        solve_part2(&parse_input(data_str))
+    }
+
+    #[test]
+    fn crt_screen() {
+        let sample = utils::string_from_sample(DAY_ID);
+        utils::snapshot::assert_snapshot(DAY_ID, "crt-sample", &screen(&sample));
+        let input = utils::string_from_input(DAY_ID);
+        utils::snapshot::assert_snapshot(DAY_ID, "crt", &screen(&input));
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        let output = utils::step::run_script(CpuCycles::new(&data), &["until cycle == 20"]);
+        assert!(output.contains("cycle=20, x=21, signal=420"), "{output}");
+        let output = utils::step::run_script(CpuCycles::new(&data), &["n 219", "s"]);
+        assert!(output.contains("cycle=220, x=18, signal=3960"), "{output}");
+    }
+}
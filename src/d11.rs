@@ -2,7 +2,7 @@
 use std::collections::VecDeque;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 11;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 11);
 
 type Res = u64;
 
@@ -48,13 +48,13 @@ struct Monkey {
 fn parse_input(data: &str) -> Vec<Monkey> {
     let mut monkeys = vec![];
 
-    for group in data.split("\n\n") {
+    for group in utils::text::blocks(data) {
         let mut items = vec![];
         let mut operation = None;
         let mut divisor = 0;
         let mut true_target = 0;
         let mut false_target = 0;
-        for line in group.lines() {
+        for line in group {
             if line.starts_with("  Starting items:") {
                 items = line[18..].split(", ").map(|i| i.parse().unwrap()).collect();
             }
@@ -122,7 +122,7 @@ fn solve_part1(data: &[Monkey]) -> Res {
                 };
                 item /= 3;
 
-                let target = if (item % monkeys[monkey_index].test.divisor) == 0 {
+                let target = if item.is_multiple_of(monkeys[monkey_index].test.divisor) {
                     monkeys[monkey_index].test.true_target
                 } else {
                     monkeys[monkey_index].test.false_target
@@ -159,7 +159,7 @@ fn solve_part2(data: &[Monkey]) -> Res {
                     Operation::Pow => item * item,
                 };
 
-                let target = if (item % monkeys[monkey_index].test.divisor) == 0 {
+                let target = if item.is_multiple_of(monkeys[monkey_index].test.divisor) {
                     monkeys[monkey_index].test.true_target
                 } else {
                     monkeys[monkey_index].test.false_target
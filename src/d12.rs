@@ -1,6 +1,6 @@
 // #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 12;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 12);
 
 use std::collections::{HashMap, VecDeque};
 
@@ -60,7 +60,8 @@ fn parse_input(data: &str) -> Maze {
     }
 }
 
-fn find_bfs(maze: &Maze, start: Index) -> usize {
+// positions from start to finish, empty when finish is unreachable
+fn find_path(maze: &Maze, start: Index) -> Vec<Index> {
     let mut queue = VecDeque::<Index>::from(vec![start]);
     let mut visited = HashMap::<Index, Index>::from([(start, start)]);
 
@@ -93,26 +94,36 @@ fn find_bfs(maze: &Maze, start: Index) -> usize {
         if coords == next_parent {
             break;
         }
-        path.push_back(*coords);
+        path.push_front(*coords);
         parent_key = *next_parent;
     }
 
-    path.len()
+    if !path.is_empty() {
+        path.push_front(start);
+    }
+    path.into()
+}
+
+fn find_bfs(maze: &Maze, start: Index) -> usize {
+    find_path(maze, start).len().saturating_sub(1)
 }
 
 fn solve_part1(maze: &Maze) -> usize {
     find_bfs(maze, maze.start())
 }
 
-fn solve_part2(maze: &Maze) -> usize {
+fn lowest_starts(maze: &Maze) -> Vec<Index> {
     let mut all_a = vec![];
     for (index, ch) in maze.map.chars().enumerate() {
         if ch == 'a' {
             all_a.push(index as Index);
         }
     }
-
     all_a
+}
+
+fn solve_part2(maze: &Maze) -> usize {
+    lowest_starts(maze)
         .iter()
         .map(|start| find_bfs(maze, *start))
         .filter(|len| *len != 0)
@@ -120,6 +131,94 @@ fn solve_part2(maze: &Maze) -> usize {
         .unwrap()
 }
 
-generate_main!();
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let maze = parse_input(data_str);
+    let path = if part == 1 {
+        find_path(&maze, maze.start())
+    } else {
+        lowest_starts(&maze)
+            .iter()
+            .map(|start| find_path(&maze, *start))
+            .filter(|path| !path.is_empty())
+            .min_by_key(Vec::len)
+            .unwrap()
+    };
+
+    let mut steps = utils::explain::Artifact::new(
+        format!("path of {} steps", path.len() - 1),
+        &["step", "row", "col", "elevation"],
+    );
+    for (step, index) in path.iter().enumerate() {
+        let elevation = maze.map.chars().nth(*index as usize).unwrap();
+        steps.row(&[
+            &step,
+            &(index / maze.cols),
+            &(index % maze.cols),
+            &elevation,
+        ]);
+    }
+    vec![steps]
+}
+
+fn check_input(data: &str) -> Vec<String> {
+    let lines = data.lines().collect::<Vec<_>>();
+    let mut violations = utils::check::uneven_lines(&lines);
+    violations.extend(utils::check::unexpected_chars(&lines, |ch| {
+        ch.is_ascii_lowercase() || ch == 'S' || ch == 'E'
+    }));
+
+    for marker in ['S', 'E'] {
+        let count = data.matches(marker).count();
+        if count != 1 {
+            violations.push(format!("found {count} `{marker}` instead of exactly one"));
+        }
+    }
+
+    violations
+}
+
+generate_main!(check = check_input, explain = explain_input);
 
 generate_tests!(31, 29);
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn explains_path() {
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 1);
+        let elevations = artifacts[0].column("elevation");
+        assert_eq!(elevations.len(), 32);
+        assert_eq!((elevations[0], elevations[31]), ("S", "E"));
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_valid() {
+        assert!(check_input(&utils::string_from_sample(DAY_ID)).is_empty());
+    }
+
+    #[test]
+    fn violations() {
+        let sample = utils::string_from_sample(DAY_ID);
+        assert_eq!(
+            check_input(&sample.replace('E', "S")),
+            [
+                "found 2 `S` instead of exactly one",
+                "found 0 `E` instead of exactly one"
+            ]
+        );
+        assert_eq!(
+            check_input("Sab\nc1E\nab"),
+            [
+                "line 3: length 2 differs from 3 of the first line",
+                "line 2, column 2: unexpected `1`",
+            ]
+        );
+    }
+}
@@ -12,26 +12,26 @@ use nom::{
 };
 
 mod utils;
-const DAY_ID: utils::DayIdType = 13;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 13);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 enum Item {
     Num(usize),
     List(Vec<Item>),
 }
 
+// packets are equal when they are in no order, so a number equals the list of that number
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Item {}
+
 impl PartialOrd for Item {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match items_ordered(self, other) {
-            Some(is_ordered) => {
-                if is_ordered {
-                    Some(Ordering::Less)
-                } else {
-                    Some(Ordering::Greater)
-                }
-            }
-            None => None,
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -86,14 +86,14 @@ fn items_ordered(left: &Item, right: &Item) -> Option<bool> {
                 // If the left list runs out of items first,
                 // the inputs are in the right order.
                 if i >= left_len && right_len > left_len {
-                    // println!("\tleft ran out");
+                    trace!("\tleft ran out");
                     return Some(true);
                 }
 
                 // If the right list runs out of items first,
                 // the inputs are not in the right order.
                 if i >= right_len && left_len > right_len {
-                    // println!("\tright ran out");
+                    trace!("\tright ran out");
                     return Some(false);
                 }
 
@@ -114,11 +114,11 @@ fn items_ordered(left: &Item, right: &Item) -> Option<bool> {
 }
 
 fn parse_input(data: &str) -> Vec<ItemPair> {
-    data.split("\n\n")
-        .map(|x| {
-            let (a, b) = x.split_once('\n').unwrap();
-            let (_, a) = item_parser(a).unwrap();
-            let (_, b) = item_parser(b).unwrap();
+    utils::text::blocks(data)
+        .iter()
+        .map(|pair| {
+            let (_, a) = item_parser(pair[0]).unwrap();
+            let (_, b) = item_parser(pair[1]).unwrap();
             (a, b)
         })
         .collect()
@@ -128,13 +128,7 @@ fn solve_part1(data: &[ItemPair]) -> usize {
     let ordered = data
         .iter()
         .enumerate()
-        .filter(|(_, (left, right))| {
-            if let Some(is_ordered) = items_ordered(left, right) {
-                is_ordered
-            } else {
-                false
-            }
-        })
+        .filter(|(_, (left, right))| items_ordered(left, right).unwrap_or_default())
         .collect::<Vec<(usize, &ItemPair)>>();
 
     ordered.iter().map(|(num, _)| num + 1).sum()
@@ -145,7 +139,7 @@ fn solve_part2(data: &[ItemPair]) -> usize {
     let divider_6: Item = Item::List(vec![Item::List(vec![Item::Num(6)])]);
 
     let mut items = vec![divider_2.clone(), divider_6.clone()];
-    for (left, right) in data.iter() {
+    for (left, right) in data {
         items.push(left.clone());
         items.push(right.clone());
     }
@@ -160,3 +154,37 @@ fn solve_part2(data: &[ItemPair]) -> usize {
 generate_main!();
 
 generate_tests!(13, 140);
+
+#[cfg(test)]
+mod ordering_tests {
+    use super::*;
+
+    fn packet(text: &str) -> Item {
+        item_parser(text).unwrap().1
+    }
+
+    #[test]
+    fn equal_packets() {
+        let (left, right) = (packet("[1,[2,[3]]]"), packet("[1,[2,[3]]]"));
+        assert_eq!(left.partial_cmp(&right), Some(Ordering::Equal));
+        assert_eq!(left.cmp(&right), Ordering::Equal);
+        assert_eq!(items_ordered(&left, &right), None);
+        // a number equals the list of that number only
+        assert_eq!(packet("[[4]]").cmp(&packet("[4]")), Ordering::Equal);
+        assert_eq!(packet("[[4]]"), packet("[4]"));
+        assert_ne!(packet("[[4]]"), packet("[4,4]"));
+    }
+
+    #[test]
+    fn ordering_agrees_with_pair_check() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        for (left, right) in &data {
+            let expected = match items_ordered(left, right) {
+                Some(true) => Ordering::Less,
+                Some(false) => Ordering::Greater,
+                None => Ordering::Equal,
+            };
+            assert_eq!(left.partial_cmp(right), Some(expected));
+        }
+    }
+}
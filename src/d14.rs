@@ -2,7 +2,7 @@
 use std::collections::HashSet;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 14;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 14);
 
 type Coord = i16;
 type Coords = (Coord, Coord);
@@ -24,7 +24,7 @@ fn parse_input(data: &str) -> Vec<Path> {
 fn init_blocks(paths: &[Path]) -> HashSet<Coords> {
     let mut blocks = HashSet::new();
 
-    for path in paths.iter() {
+    for path in paths {
         for win in path.windows(2) {
             let (from_x, from_y) = win[0];
             let (to_x, to_y) = win[1];
@@ -52,128 +52,151 @@ fn init_blocks(paths: &[Path]) -> HashSet<Coords> {
     blocks
 }
 
-fn solve_part1(paths: &[Path]) -> usize {
-    let mut abyss_y = 0;
-    for (_x, y) in paths.iter().flatten() {
-        if *y > abyss_y {
-            abyss_y = *y;
-        }
-    }
+fn lowest_stone(paths: &[Path]) -> Coord {
+    paths.iter().flatten().map(|&(_x, y)| y).max().unwrap_or(0)
+}
 
-    let mut blocks = init_blocks(paths);
+const SAND_SOURCE: Coords = (500, 0);
 
-    // let init_blocks = blocks.clone();
+// sand poured one grain at a time, yields resting position of every grain
+struct SandGrains {
+    stones: HashSet<Coords>,
+    blocks: HashSet<Coords>,
+    abyss_y: Coord,
+    floor: Option<Coord>,
+    grains: usize,
+}
 
-    let stone_blocks = blocks.len();
+impl SandGrains {
+    pub fn new(paths: &[Path], with_floor: bool) -> Self {
+        let stones = init_blocks(paths);
+        let abyss_y = lowest_stone(paths);
+        Self {
+            blocks: stones.clone(),
+            stones,
+            abyss_y,
+            floor: with_floor.then_some(abyss_y + 2),
+            grains: 0,
+        }
+    }
+}
 
-    let mut falling_sand = (500, 0);
-    loop {
-        let (x, y) = falling_sand;
+impl Iterator for SandGrains {
+    type Item = Coords;
 
-        if y > abyss_y {
-            break;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.blocks.contains(&SAND_SOURCE) {
+            return None;
         }
 
-        if !blocks.contains(&(x, y + 1)) {
-            falling_sand.1 += 1;
-            continue;
-        }
-        if !blocks.contains(&(x - 1, y + 1)) {
-            falling_sand.0 -= 1;
-            falling_sand.1 += 1;
-            continue;
-        }
-        if !blocks.contains(&(x + 1, y + 1)) {
-            falling_sand.0 += 1;
-            falling_sand.1 += 1;
-            continue;
-        }
+        let (mut x, mut y) = SAND_SOURCE;
+        loop {
+            match self.floor {
+                None if y > self.abyss_y => return None,
+                Some(floor) if y == floor - 1 => break,
+                _ => {}
+            }
 
-        blocks.insert(falling_sand);
+            if !self.blocks.contains(&(x, y + 1)) {
+                y += 1;
+                continue;
+            }
+            if !self.blocks.contains(&(x - 1, y + 1)) {
+                x -= 1;
+                y += 1;
+                continue;
+            }
+            if !self.blocks.contains(&(x + 1, y + 1)) {
+                x += 1;
+                y += 1;
+                continue;
+            }
+            break;
+        }
 
-        falling_sand = (500, 0);
+        self.blocks.insert((x, y));
+        self.grains += 1;
+        Some((x, y))
     }
-
-    // print_blocks(paths, &init_blocks, &blocks);
-
-    blocks.len() - stone_blocks
 }
 
-fn solve_part2(paths: &[Path]) -> usize {
-    let mut abyss_y = 0;
-    for (_x, y) in paths.iter().flatten() {
-        if *y > abyss_y {
-            abyss_y = *y;
-        }
+impl utils::step::Inspect for SandGrains {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![utils::step::value("grains", self.grains)]
     }
 
-    let floor = abyss_y + 2;
-
-    let mut blocks = init_blocks(paths);
-
-    // let init_blocks = blocks.clone();
+    fn render(&self) -> String {
+        render_blocks(&self.stones, &self.blocks, self.abyss_y)
+    }
+}
 
-    let stone_blocks = blocks.len();
+// reference solution - every grain falls all the way from the source
+fn count_grains_reference(paths: &[Path], with_floor: bool) -> usize {
+    SandGrains::new(paths, with_floor).count()
+}
 
-    let mut sand = (500, 0);
-    loop {
-        let (x, y) = sand;
+// grain follows the path of the previous one up to the place where that one came to rest,
+// so the path is kept and each grain starts falling from its last free position,
+// returns count of grains and all the blocks with sand at rest
+fn pour_sand(paths: &[Path], with_floor: bool) -> (usize, HashSet<Coords>) {
+    let mut blocks = init_blocks(paths);
+    let abyss_y = lowest_stone(paths);
+    let floor = with_floor.then_some(abyss_y + 2);
 
-        if y == floor - 1 {
-            if x == 500 && y == 0 {
-                break;
-            }
-            blocks.insert(sand);
-            sand = (500, 0);
-            continue;
+    let mut grains = 0;
+    let mut path = vec![SAND_SOURCE];
+    while let Some(&(x, y)) = path.last() {
+        if floor.is_none() && y > abyss_y {
+            break;
         }
 
-        if !blocks.contains(&(x, y + 1)) {
-            sand.1 += 1;
-            continue;
-        }
-        if !blocks.contains(&(x - 1, y + 1)) {
-            sand.0 -= 1;
-            sand.1 += 1;
-            continue;
-        }
-        if !blocks.contains(&(x + 1, y + 1)) {
-            sand.0 += 1;
-            sand.1 += 1;
-            continue;
+        let next = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+            .into_iter()
+            .find(|coords| !blocks.contains(coords) && floor.is_none_or(|floor| coords.1 < floor));
+        if let Some(coords) = next {
+            path.push(coords);
+        } else {
+            blocks.insert((x, y));
+            grains += 1;
+            path.pop();
         }
+    }
 
-        blocks.insert(sand);
+    (grains, blocks)
+}
 
-        if x == 500 && y == 0 {
-            break;
-        }
+fn solve_part1(paths: &[Path]) -> usize {
+    let (grains, blocks) = pour_sand(paths, false);
 
-        sand = (500, 0);
-    }
+    verbose!(
+        "{}",
+        render_blocks(&init_blocks(paths), &blocks, lowest_stone(paths))
+    );
 
-    // print_blocks(paths, &init_blocks, &blocks);
+    grains
+}
+
+fn solve_part2(paths: &[Path]) -> usize {
+    let (grains, blocks) = pour_sand(paths, true);
+
+    verbose!(
+        "{}",
+        render_blocks(&init_blocks(paths), &blocks, lowest_stone(paths))
+    );
 
-    blocks.len() - stone_blocks
+    grains
 }
 
-#[allow(dead_code)]
-fn print_blocks(paths: &[Path], init_blocks: &HashSet<Coords>, blocks: &HashSet<Coords>) {
+fn render_blocks(stones: &HashSet<Coords>, blocks: &HashSet<Coords>, y1: Coord) -> String {
     const BLOCK_STR: &str = "\u{2588}";
 
-    let mut y1 = 0;
-    for &(_, y) in paths.iter().flatten() {
-        if y > y1 {
-            y1 = y;
-        }
-    }
-
+    let mut picture = String::new();
     for y in 0..=(y1 + 2) {
         for x in (500 - y1 - 2)..=(500 + y1 + 2) {
             let char = if y == y1 + 2 {
                 BLOCK_STR
             } else if blocks.contains(&(x, y)) {
-                if init_blocks.contains(&(x, y)) {
+                if stones.contains(&(x, y)) {
                     BLOCK_STR
                 } else {
                     "o"
@@ -181,12 +204,107 @@ fn print_blocks(paths: &[Path], init_blocks: &HashSet<Coords>, blocks: &HashSet<
             } else {
                 "."
             };
-            print!("{char}");
+            picture.push_str(char);
         }
-        println!();
+        picture.push('\n');
     }
+    picture
 }
 
-generate_main!();
+fn step_through(data_str: &str, part: u8) {
+    let paths = parse_input(data_str);
+    utils::step::prompt(SandGrains::new(&paths, part == 2));
+}
+
+// compares grain counts of both parts
+fn differential(cases: usize, seed: u64) -> Option<utils::differential::Divergence> {
+    utils::differential::first_divergence(
+        cases,
+        seed,
+        |rng| {
+            let paths = rng.index(1..=6);
+            (0..paths)
+                .map(|_| {
+                    let mut point = (rng.range(490..=510), rng.range(2..=15));
+                    let mut points = vec![point];
+                    for segment in 0..rng.index(0..=3) {
+                        let length = rng.range(-4..=4);
+                        if segment % 2 == 0 {
+                            point.0 += length;
+                        } else {
+                            point.1 = (point.1 + length).max(1);
+                        }
+                        points.push(point);
+                    }
+                    points
+                        .iter()
+                        .map(|(x, y)| format!("{x},{y}"))
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        |input| {
+            let paths = parse_input(input);
+            (
+                count_grains_reference(&paths, false),
+                count_grains_reference(&paths, true),
+            )
+        },
+        |input| {
+            let paths = parse_input(input);
+            (pour_sand(&paths, false).0, pour_sand(&paths, true).0)
+        },
+    )
+}
+
+generate_main!(step = step_through, differential = differential);
 
 generate_tests!(24, 93);
+
+#[cfg(test)]
+mod differential_tests {
+    #[test]
+    fn optimized_matches_reference() {
+        if let Some(divergence) = super::differential(300, 14) {
+            panic!("{divergence}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    #[test]
+    fn final_sand_picture() {
+        let paths = parse_input(&utils::string_from_sample(DAY_ID));
+        for (part, with_floor) in [(1, false), (2, true)] {
+            let (_grains, blocks) = pour_sand(&paths, with_floor);
+            let picture = render_blocks(&init_blocks(&paths), &blocks, lowest_stone(&paths));
+            utils::snapshot::assert_snapshot(DAY_ID, &format!("sand-part{part}"), &picture);
+        }
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let paths = parse_input(&utils::string_from_sample(DAY_ID));
+        let output = utils::step::run_script(SandGrains::new(&paths, false), &["n 1000"]);
+        assert!(
+            output.contains("simulation finished after 24 steps\ngrains=24"),
+            "{output}"
+        );
+        let output =
+            utils::step::run_script(SandGrains::new(&paths, true), &["until grains >= 93", "s"]);
+        assert!(
+            output.contains("simulation finished after 93 steps"),
+            "{output}"
+        );
+    }
+}
@@ -2,7 +2,9 @@
 use std::collections::HashSet;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 15;
+use utils::interval::{Interval, IntervalSet};
+
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 15);
 
 type Coord = i64;
 type Coords = (Coord, Coord);
@@ -32,19 +34,16 @@ fn coord_dist(x: Coord, y: Coord) -> Coord {
     }
 }
 
-fn solve_part1(data: &[(Coords, Coords)]) -> usize {
-    #[cfg(test)]
-    const ROW_OF_INTEREST: Coord = 10;
-    #[cfg(not(test))]
-    const ROW_OF_INTEREST: Coord = 2_000_000;
-
+// reference solution - collects every covered cell of the row,
+// assumes exactly one beacon lies in the row
+fn covered_in_row_reference(data: &[(Coords, Coords)], row: Coord) -> usize {
     let mut intervals: Vec<(Coord, Coord)> = vec![];
 
-    for &((sx, sy), (bx, by)) in data.iter() {
+    for &((sx, sy), (bx, by)) in data {
         let bs_distance = coord_dist(bx, sx) + coord_dist(by, sy);
 
         for y in (sy - bs_distance)..=(sy + bs_distance) {
-            if y == ROW_OF_INTEREST {
+            if y == row {
                 let d = coord_dist(y, sy);
                 let interval_len = bs_distance - d;
                 let interval = (sx - interval_len, sx + interval_len);
@@ -63,37 +62,102 @@ fn solve_part1(data: &[(Coords, Coords)]) -> usize {
     cells.len() - 1
 }
 
+// cells of the row closer to some sensor than its beacon
+fn row_coverage(data: &[(Coords, Coords)], row: Coord) -> IntervalSet {
+    data.iter()
+        .filter_map(|&((sx, sy), (bx, by))| {
+            let bs_distance = coord_dist(bx, sx) + coord_dist(by, sy);
+            let interval_len = bs_distance - coord_dist(row, sy);
+            (interval_len >= 0).then(|| Interval::new(sx - interval_len, sx + interval_len))
+        })
+        .collect()
+}
+
+// cells of the row where the beacon cannot be, those of known beacons excluded
+fn covered_in_row(data: &[(Coords, Coords)], row: Coord) -> usize {
+    let covered = row_coverage(data, row).len();
+
+    let beacons = data
+        .iter()
+        .map(|&(_sensor, beacon)| beacon)
+        .filter(|&(_bx, by)| by == row)
+        .collect::<HashSet<_>>();
+
+    usize::try_from(covered).unwrap() - beacons.len()
+}
+
+fn solve_part1(data: &[(Coords, Coords)]) -> usize {
+    #[cfg(test)]
+    const ROW_OF_INTEREST: Coord = 10;
+    #[cfg(not(test))]
+    const ROW_OF_INTEREST: Coord = 2_000_000;
+
+    covered_in_row(data, ROW_OF_INTEREST)
+}
+
 fn solve_part2(data: &[(Coords, Coords)]) -> Coord {
     #[cfg(test)]
     const LIMIT: Coord = 20;
     #[cfg(not(test))]
     const LIMIT: Coord = 4_000_000;
 
-    let mut aux: Vec<(Coord, Coord, Coord)> = vec![];
-    for &((sx, sy), (bx, by)) in data.iter() {
-        let distance = coord_dist(bx, sx) + coord_dist(by, sy);
-        aux.push((sx, sy, distance));
-    }
-
-    for yy in 0..=LIMIT {
-        let mut xx = 0;
-        'next: while xx <= LIMIT {
-            for &(sx, sy, bs_distance) in &aux {
-                let my_distance = coord_dist(xx, sx) + coord_dist(yy, sy);
-                if my_distance <= bs_distance {
-                    let d = coord_dist(yy, sy);
-                    let interval_len = bs_distance - d;
-                    xx = sx + interval_len + 1;
-                    continue 'next;
-                }
-            }
-            return xx * 4_000_000 + yy;
-        }
-    }
+    // the only cell within the limits covered by no sensor
+    let bounds = Interval::new(0, LIMIT);
+    (0..=LIMIT)
+        .find_map(|y| {
+            let gaps = row_coverage(data, y).gaps(bounds);
+            gaps.first().map(|gap| gap.start * 4_000_000 + y)
+        })
+        .unwrap()
+}
 
-    unreachable!();
+const DIFFERENTIAL_ROW: Coord = 10;
+
+// inputs keep the assumption of the reference solution - single beacon in the row
+fn differential(cases: usize, seed: u64) -> Option<utils::differential::Divergence> {
+    utils::differential::first_divergence(
+        cases,
+        seed,
+        |rng| {
+            let sensors = rng.index(1..=8);
+            let row_beacon = (rng.range(-20..=20), DIFFERENTIAL_ROW);
+            let mut beacons = vec![row_beacon];
+            (0..sensors)
+                .map(|sensor| {
+                    let (sx, sy) = (rng.range(-20..=20), rng.range(-10..=30));
+                    let (bx, by) = if sensor == 0 {
+                        row_beacon
+                    } else if beacons.len() > 1 && rng.index(0..=2) == 0 {
+                        // beacons are shared by several sensors now and then
+                        beacons[rng.index(1..=beacons.len() - 1)]
+                    } else {
+                        let mut by = rng.range(-10..=30);
+                        if by == DIFFERENTIAL_ROW {
+                            by += 1;
+                        }
+                        beacons.push((rng.range(-20..=20), by));
+                        beacons[beacons.len() - 1]
+                    };
+                    format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        |input| covered_in_row_reference(&parse_input(input), DIFFERENTIAL_ROW),
+        |input| covered_in_row(&parse_input(input), DIFFERENTIAL_ROW),
+    )
 }
 
-generate_main!();
+generate_main!(differential = differential);
 
 generate_tests!(26, 56_000_011);
+
+#[cfg(test)]
+mod differential_tests {
+    #[test]
+    fn optimized_matches_reference() {
+        if let Some(divergence) = super::differential(500, 15) {
+            panic!("{divergence}");
+        }
+    }
+}
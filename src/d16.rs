@@ -2,7 +2,7 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
 mod utils;
-const DAY_ID: utils::DayIdType = 16;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 16);
 
 #[derive(Debug, Clone)]
 struct Valve {
@@ -59,7 +59,7 @@ fn find_shortest_distance(volcano_map: &HashMap<&str, &Valve>, start: &str, fini
 
         let neighbors = &volcano_map.get(node).unwrap().tunnels;
 
-        for neighbor in neighbors.iter() {
+        for neighbor in neighbors {
             if neighbor == from {
                 continue;
             }
@@ -71,13 +71,27 @@ fn find_shortest_distance(volcano_map: &HashMap<&str, &Valve>, start: &str, fini
     distance
 }
 
+// valve opened at the minute by the one who walked there
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Opening<'data> {
+    minute: usize,
+    valve: &'data str,
+    by: &'static str,
+}
+
 struct PipeSystem<'data> {
     valves: &'data [Valve],
     distances: HashMap<(&'data str, &'data str), usize>,
+    minutes: usize,
 }
 
+// time before the volcano erupts, can be changed in REPL with `set minutes=<value>`
+const MINUTES: usize = 30;
+// time spent teaching the elephant to help
+const TEACHING_MINUTES: usize = 4;
+
 impl<'data> PipeSystem<'data> {
-    pub fn new(valves: &'data [Valve]) -> Self {
+    pub fn new(valves: &'data [Valve], minutes: usize) -> Self {
         let valve_names = valves.iter().map(|v| v.name.as_str()).collect::<Vec<_>>();
         let volcano_map = valves.iter().map(|v| (v.name.as_str(), v)).collect();
 
@@ -93,10 +107,21 @@ impl<'data> PipeSystem<'data> {
             }
         }
 
-        Self { valves, distances }
+        verbose!(
+            "{} valves, {} with non-zero flow rate",
+            valves.len(),
+            valves.iter().filter(|v| v.flow_rate > 0).count()
+        );
+        trace!("distances: {distances:?}");
+
+        Self {
+            valves,
+            distances,
+            minutes,
+        }
     }
 
-    pub fn productive_valves(&self) -> Vec<(&str, usize)> {
+    pub fn productive_valves(&self) -> Vec<(&'data str, usize)> {
         self.valves
             .iter()
             .filter(|v| v.flow_rate > 0)
@@ -108,23 +133,38 @@ impl<'data> PipeSystem<'data> {
         *self.distances.get(&(from, to)).unwrap()
     }
 
-    fn highest_pressure(&self, t: usize, from: &str, seen: &HashSet<&str>) -> usize {
-        const MINUTES: usize = 30;
-
-        let mut best = 0;
+    // highest pressure released with the valves opened to get it
+    fn highest_pressure(
+        &self,
+        t: usize,
+        from: &str,
+        seen: &HashSet<&str>,
+    ) -> (usize, Vec<Opening<'data>>) {
+        let mut best = (0, vec![]);
 
         let productive_valves = self.productive_valves();
 
         for (to, to_flow) in productive_valves {
             if !seen.contains(&to) {
                 let new_t = t + self.min_distance(from, to) + 1;
-                if new_t <= MINUTES {
+                if new_t <= self.minutes {
                     let mut new_seen = seen.clone();
                     new_seen.insert(to);
-                    let a =
-                        self.highest_pressure(new_t, to, &new_seen) + (MINUTES - new_t) * to_flow;
-                    if a > best {
-                        best = a;
+                    let (pressure, mut openings) = self.highest_pressure(new_t, to, &new_seen);
+                    let a = pressure + (self.minutes - new_t) * to_flow;
+                    if seen.is_empty() {
+                        verbose!("first valve {to} at minute {new_t}: {a}");
+                    }
+                    if a > best.0 {
+                        openings.insert(
+                            0,
+                            Opening {
+                                minute: new_t,
+                                valve: to,
+                                by: "you",
+                            },
+                        );
+                        best = (a, openings);
                     }
                 }
             }
@@ -133,7 +173,7 @@ impl<'data> PipeSystem<'data> {
         best
     }
 
-    pub fn get_max_pressure(&self) -> usize {
+    pub fn get_max_pressure(&self) -> (usize, Vec<Opening<'data>>) {
         let seen = HashSet::new();
         self.highest_pressure(0, "AA", &seen)
     }
@@ -145,28 +185,42 @@ impl<'data> PipeSystem<'data> {
         me: (usize, &str),
         helper: (usize, &str),
         visited: &HashSet<&str>,
-    ) -> usize {
-        const MINUTES: usize = 26;
-
-        let mut best = 0;
+    ) -> (usize, Vec<Opening<'data>>) {
+        let mut best = (0, vec![]);
 
         let productive_valves = self.productive_valves();
 
         for (to, to_flow) in productive_valves {
+            // out of time budget, best pressure found so far is returned
+            if utils::budget::exhausted() {
+                break;
+            }
             if !visited.contains(&to) {
                 let (t, from) = if helpers_turn { helper } else { me };
                 let new_t = t + self.min_distance(from, to) + 1;
-                if new_t <= MINUTES {
+                if new_t <= self.minutes {
                     let mut new_visited = visited.clone();
                     new_visited.insert(to);
-                    let a = self.highest_pressure_with_helper(
+                    let (pressure, mut openings) = self.highest_pressure_with_helper(
                         !helpers_turn,
                         if helpers_turn { me } else { (new_t, to) },
                         if helpers_turn { (new_t, to) } else { helper },
                         &new_visited,
-                    ) + (MINUTES - new_t) * to_flow;
-                    if a > best {
-                        best = a;
+                    );
+                    let a = pressure + (self.minutes - new_t) * to_flow;
+                    if visited.is_empty() {
+                        verbose!("first valve {to} at minute {new_t}: {a}");
+                    }
+                    if a > best.0 {
+                        openings.insert(
+                            0,
+                            Opening {
+                                minute: new_t,
+                                valve: to,
+                                by: if helpers_turn { "elephant" } else { "you" },
+                            },
+                        );
+                        best = (a, openings);
                     }
                 }
             }
@@ -175,22 +229,171 @@ impl<'data> PipeSystem<'data> {
         best
     }
 
-    pub fn get_max_pressure_with_helper(&self) -> usize {
+    pub fn get_max_pressure_with_helper(&self) -> (usize, Vec<Opening<'data>>) {
         let seen = HashSet::new();
         self.highest_pressure_with_helper(false, (0, "AA"), (0, "AA"), &seen)
     }
 }
 
+fn best_openings(valves: &[Valve], part: u8) -> (usize, Vec<Opening<'_>>) {
+    let minutes = utils::params::get("minutes", MINUTES);
+    if part == 1 {
+        PipeSystem::new(valves, minutes).get_max_pressure()
+    } else {
+        let system = PipeSystem::new(valves, minutes.saturating_sub(TEACHING_MINUTES));
+        system.get_max_pressure_with_helper()
+    }
+}
+
 fn solve_part1(valves: &[Valve]) -> usize {
-    let system = PipeSystem::new(valves);
-    system.get_max_pressure()
+    best_openings(valves, 1).0
 }
 
 fn solve_part2(valves: &[Valve]) -> usize {
-    let system = PipeSystem::new(valves);
-    system.get_max_pressure_with_helper()
+    best_openings(valves, 2).0
+}
+
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let valves = parse_input(data_str);
+    let (pressure, mut openings) = best_openings(&valves, part);
+    openings.sort_by_key(|opening| opening.minute);
+    let flow_rates = valves
+        .iter()
+        .map(|valve| (valve.name.as_str(), valve.flow_rate))
+        .collect::<HashMap<_, _>>();
+    let minutes = if part == 1 {
+        utils::params::get("minutes", MINUTES)
+    } else {
+        utils::params::get("minutes", MINUTES).saturating_sub(TEACHING_MINUTES)
+    };
+
+    let mut order = utils::explain::Artifact::new(
+        format!("valves opened, {pressure} pressure released"),
+        &["minute", "valve", "by", "flow rate", "released"],
+    );
+    for opening in openings {
+        let flow_rate = flow_rates[opening.valve];
+        order.row(&[
+            &opening.minute,
+            &opening.valve,
+            &opening.by,
+            &flow_rate,
+            &((minutes - opening.minute) * flow_rate),
+        ]);
+    }
+    vec![order]
 }
 
-generate_main!();
+generate_main!(explain = explain_input);
 
 generate_tests!(1651, 1707);
+
+#[cfg(test)]
+mod repl_tests {
+    use super::*;
+
+    #[test]
+    fn set_minutes_in_repl() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let valves = parse_input(&data_str);
+        let mut output = vec![];
+        utils::repl::prompt_with(
+            DAY_ID,
+            &data_str,
+            std::time::Duration::ZERO,
+            &valves,
+            [&|data| solve_part1(data).to_string(), &|data| {
+                solve_part2(data).to_string()
+            }],
+            "part1\nset minutes=20\npart1\nunset minutes\nstats\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("part 1: 1651 ("));
+        assert!(output.contains("[was 1651]"));
+        assert!(output.contains("part 1: 2 run(s)"));
+        assert_eq!(utils::params::get("minutes", MINUTES), MINUTES);
+    }
+
+    #[test]
+    fn time_budget_in_repl() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let valves = parse_input(&data_str);
+        utils::budget::set_limit(Some(std::time::Duration::from_micros(1)));
+        let mut output = vec![];
+        utils::repl::prompt_with(
+            DAY_ID,
+            &data_str,
+            std::time::Duration::ZERO,
+            &valves,
+            [&|data| solve_part1(data).to_string(), &|data| {
+                solve_part2(data).to_string()
+            }],
+            "part2\npart2\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output
+                .matches("[timed out, best answer found so far]")
+                .count(),
+            2,
+            "{output}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_budget_reports_best_so_far() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::budget::set_limit(Some(std::time::Duration::ZERO));
+
+        let mut results = vec![];
+        solve_input(&data_str, &[2], &mut |result| results.push(result));
+
+        let part2 = results.last().unwrap();
+        assert!(part2.timed_out);
+        let answer = part2.answer.as_deref().unwrap().parse::<usize>().unwrap();
+        assert!(answer < 1707);
+    }
+
+    #[test]
+    fn short_budget_reports_partial_pressure() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::budget::set_limit(Some(std::time::Duration::from_micros(1)));
+
+        let mut results = vec![];
+        solve_input(&data_str, &[2], &mut |result| results.push(result));
+
+        let part2 = results.last().unwrap();
+        assert!(part2.timed_out);
+        let answer = part2.answer.as_deref().unwrap().parse::<usize>().unwrap();
+        assert!(0 < answer && answer < 1707);
+    }
+}
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn explains_valve_order() {
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 1);
+        assert_eq!(
+            artifacts[0].column("valve"),
+            ["DD", "BB", "JJ", "HH", "EE", "CC"]
+        );
+        assert_eq!(
+            artifacts[0].column("minute"),
+            ["2", "5", "9", "17", "21", "24"]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use std::collections::HashSet;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 17;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 17);
 
 fn parse_input(data: &str) -> &str {
     data
@@ -115,81 +115,154 @@ const WELL_WIDTH: Coord = 7;
 const BLOCK_INIT_X: Coord = 2;
 const BLOCK_INIT_DY: Coord = 3;
 
-fn solve_part1(data: &str) -> Coord {
-    const BLOCK_COUNT: usize = 2022;
-
-    let mut blocks_fallen = 0;
-    let mut highest_point: Coord = 0;
-
-    let mut block = Block::from_step(blocks_fallen);
+// well with all the blocks at rest and the single one currently falling
+struct Chamber {
+    well: HashSet<Coords>,
+    highest_point: Coord,
+    blocks_fallen: usize,
+    block: Block,
+    block_position: Coords,
+}
 
-    let mut block_position = (BLOCK_INIT_X, highest_point + BLOCK_INIT_DY);
+impl Chamber {
+    pub fn new() -> Self {
+        let mut well = HashSet::<Coords>::new();
+        for x in 0..WELL_WIDTH {
+            well.insert((x, -1));
+        }
 
-    let mut well = HashSet::<Coords>::new();
-    for x in 0..WELL_WIDTH {
-        well.insert((x, -1));
+        Self {
+            well,
+            highest_point: 0,
+            blocks_fallen: 0,
+            block: Block::from_step(0),
+            block_position: (BLOCK_INIT_X, BLOCK_INIT_DY),
+        }
     }
 
-    let mut char_index = 0usize;
-    let char_count = data.chars().count();
-    loop {
-        let ch = data.chars().nth(char_index).unwrap();
-        char_index += 1;
-        char_index %= char_count;
-
-        match ch {
+    // pushes falling block by a jet of gas and lets it fall by one unit,
+    // returns the block with its position once it comes to rest
+    pub fn apply_jet(&mut self, jet: char) -> Option<(Block, Coords)> {
+        let block = self.block;
+        match jet {
             '>' => {
-                if block_position.0 < WELL_WIDTH - block.width()
-                    && !well_includes(&well, &block.coords_on_right_of(block_position))
+                if self.block_position.0 < WELL_WIDTH - block.width()
+                    && !well_includes(&self.well, &block.coords_on_right_of(self.block_position))
                 {
-                    block_position.0 += 1;
+                    self.block_position.0 += 1;
                 }
             }
             '<' => {
-                if block_position.0 != 0
-                    && !well_includes(&well, &block.coords_on_left_of(block_position))
+                if self.block_position.0 != 0
+                    && !well_includes(&self.well, &block.coords_on_left_of(self.block_position))
                 {
-                    block_position.0 -= 1;
+                    self.block_position.0 -= 1;
                 }
             }
             _ => unreachable!(),
         }
 
-        if well_includes(&well, &block.coords_below_of(block_position)) {
-            for coords in block.taken_coords(block_position) {
-                well.insert(coords);
+        if well_includes(&self.well, &block.coords_below_of(self.block_position)) {
+            let rest_position = self.block_position;
+            for coords in block.taken_coords(rest_position) {
+                self.well.insert(coords);
             }
-            highest_point = highest_point.max(block_position.1 + block.height());
-
-            blocks_fallen += 1;
-            block = Block::from_step(blocks_fallen);
-            block_position = (BLOCK_INIT_X, highest_point + BLOCK_INIT_DY);
+            self.highest_point = self.highest_point.max(rest_position.1 + block.height());
+            self.blocks_fallen += 1;
+            self.block = Block::from_step(self.blocks_fallen);
+            self.block_position = (BLOCK_INIT_X, self.highest_point + BLOCK_INIT_DY);
+            Some((block, rest_position))
+        } else {
+            self.block_position.1 -= 1;
+            None
+        }
+    }
 
-            if blocks_fallen == BLOCK_COUNT {
-                break;
+    // top rows of the well, falling block drawn as `@`
+    pub fn render(&self, rows: Coord) -> String {
+        let falling = self.block.taken_coords(self.block_position);
+        let top = self.block_position.1 + self.block.height() - 1;
+        let bottom = (top - rows + 1).max(0);
+
+        let mut picture = String::new();
+        for y in (bottom..=top).rev() {
+            picture.push('|');
+            for x in 0..WELL_WIDTH {
+                picture.push(if falling.contains(&(x, y)) {
+                    '@'
+                } else if self.well.contains(&(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                });
             }
-        } else {
-            block_position.1 -= 1;
+            picture.push_str("|\n");
+        }
+        if bottom == 0 {
+            picture.push_str("+-------+\n");
         }
+        picture
     }
+}
 
-    highest_point
+// rocks dropped one at a time, yields every rock with position it came to rest at
+struct RockFall {
+    chamber: Chamber,
+    jets: Vec<char>,
+    jet_index: usize,
 }
 
-fn solve_part2(data: &str) -> Coord {
-    const BLOCK_COUNT: usize = 1_000_000_000_000;
+impl RockFall {
+    pub fn new(data: &str) -> Self {
+        Self {
+            chamber: Chamber::new(),
+            jets: data.chars().collect(),
+            jet_index: 0,
+        }
+    }
+}
 
-    let mut blocks_fallen = 0;
-    let mut highest_point: Coord = 0;
+impl Iterator for RockFall {
+    type Item = (Block, Coords);
 
-    let mut block = Block::from_step(blocks_fallen);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let jet = self.jets[self.jet_index];
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
+            if let Some(rested) = self.chamber.apply_jet(jet) {
+                return Some(rested);
+            }
+        }
+    }
+}
 
-    let mut block_position = (BLOCK_INIT_X, highest_point + BLOCK_INIT_DY);
+impl utils::step::Inspect for RockFall {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            utils::step::value("rocks", self.chamber.blocks_fallen),
+            utils::step::value("height", self.chamber.highest_point),
+            utils::step::value("jet", self.jet_index),
+        ]
+    }
 
-    let mut well = HashSet::<Coords>::new();
-    for x in 0..WELL_WIDTH {
-        well.insert((x, -1));
+    fn render(&self) -> String {
+        self.chamber.render(20)
     }
+}
+
+fn solve_part1(data: &str) -> Coord {
+    const BLOCK_COUNT: usize = 2022;
+
+    let mut rocks = RockFall::new(data);
+    rocks.by_ref().take(BLOCK_COUNT).for_each(drop);
+
+    rocks.chamber.highest_point
+}
+
+fn solve_part2(data: &str) -> Coord {
+    const BLOCK_COUNT: usize = 1_000_000_000_000;
+
+    let mut chamber = Chamber::new();
 
     let mut cycle_blocks_heights: Vec<(usize, Coord)> = vec![];
 
@@ -206,75 +279,72 @@ fn solve_part2(data: &str) -> Coord {
         char_index %= char_count;
 
         if char_index == 0 {
-            let block_diff = blocks_fallen - latest_blocks;
-            let height_diff = highest_point - latest_height;
+            let block_diff = chamber.blocks_fallen - latest_blocks;
+            let height_diff = chamber.highest_point - latest_height;
             cycle_blocks_heights.push((block_diff, height_diff));
 
-            // println!(
-            //     "After {blocks_fallen} blocks (current height: {highest_point}),\n\tcycles data: {cycle_blocks_heights:?}"
-            // );
+            trace!(
+                "After {} blocks (current height: {}),\n\tcycles data: {cycle_blocks_heights:?}",
+                chamber.blocks_fallen,
+                chamber.highest_point
+            );
 
             let len = cycle_blocks_heights.len();
             if len > 1 {
                 let prev = cycle_blocks_heights[len - 2];
                 let current = cycle_blocks_heights[len - 1];
                 if prev == current {
-                    skipped_loops = (BLOCK_COUNT - blocks_fallen) / current.0;
-                    // println!("stable loop -> size {current:?}, on {blocks_fallen}, skipped loops: {skipped_loops}", );
-                    blocks_fallen += skipped_loops * current.0;
-                    // println!("jumping to {blocks_fallen} blocks");
+                    skipped_loops = (BLOCK_COUNT - chamber.blocks_fallen) / current.0;
+                    verbose!(
+                        "stable loop -> size {current:?}, on {}, skipped loops: {skipped_loops}",
+                        chamber.blocks_fallen
+                    );
+                    chamber.blocks_fallen += skipped_loops * current.0;
+                    verbose!("jumping to {} blocks", chamber.blocks_fallen);
                 }
             }
 
-            latest_blocks = blocks_fallen;
-            latest_height = highest_point;
+            latest_blocks = chamber.blocks_fallen;
+            latest_height = chamber.highest_point;
         }
 
-        match ch {
-            '>' => {
-                if block_position.0 < WELL_WIDTH - block.width()
-                    && !well_includes(&well, &block.coords_on_right_of(block_position))
-                {
-                    block_position.0 += 1;
-                }
-            }
-            '<' => {
-                if block_position.0 != 0
-                    && !well_includes(&well, &block.coords_on_left_of(block_position))
-                {
-                    block_position.0 -= 1;
-                }
-            }
-            _ => unreachable!(),
-        }
-
-        if well_includes(&well, &block.coords_below_of(block_position)) {
-            for coords in block.taken_coords(block_position) {
-                well.insert(coords);
-            }
-            highest_point = highest_point.max(block_position.1 + block.height());
-            blocks_fallen += 1;
-            block = Block::from_step(blocks_fallen);
-            block_position = (BLOCK_INIT_X, highest_point + BLOCK_INIT_DY);
-
-            if blocks_fallen == BLOCK_COUNT {
-                break;
-            }
-        } else {
-            block_position.1 -= 1;
+        if chamber.apply_jet(ch).is_some() && chamber.blocks_fallen == BLOCK_COUNT {
+            break;
         }
     }
 
     let catch_up = if skipped_loops > 0 {
-        // println!("\tFull loops skipped: {skipped_loops} with height: {highest_point}\n\tcycles data: {cycle_blocks_heights:?}");
+        verbose!(
+            "\tFull loops skipped: {skipped_loops} with height: {}\n\tcycles data: {cycle_blocks_heights:?}",
+            chamber.highest_point
+        );
         (skipped_loops as Coord) * cycle_blocks_heights[cycle_blocks_heights.len() - 1].1
     } else {
         0
     };
 
-    highest_point + catch_up
+    chamber.highest_point + catch_up
+}
+
+fn step_through(data_str: &str, _part: u8) {
+    utils::step::prompt(RockFall::new(parse_input(data_str)));
 }
 
-generate_main!();
+generate_main!(step = step_through);
 
 generate_tests!(3068, 1_514_285_714_288);
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let data = parse_input(&data_str);
+        let output = utils::step::run_script(RockFall::new(data), &["until rocks == 2022"]);
+        assert!(output.contains("rocks=2022, height=3068"), "{output}");
+        let output = utils::step::run_script(RockFall::new(data), &["s", "s"]);
+        assert!(output.contains("rocks=2, height=4"), "{output}");
+    }
+}
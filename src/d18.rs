@@ -1,22 +1,23 @@
 #![warn(clippy::pedantic)]
 use std::collections::{HashSet, VecDeque};
+use std::io::{self, BufRead};
 
 mod utils;
-const DAY_ID: utils::DayIdType = 18;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 18);
 
 type Coord = i64;
 type Cube = (Coord, Coord, Coord);
 
+fn parse_cube(line: &str) -> Cube {
+    let coords = line.split(',').collect::<Vec<_>>();
+    let x = coords[0].parse().unwrap();
+    let y = coords[1].parse().unwrap();
+    let z = coords[2].parse().unwrap();
+    (x, y, z)
+}
+
 fn parse_input(data: &str) -> Vec<Cube> {
-    data.lines()
-        .map(|line| {
-            let coords = line.split(',').collect::<Vec<_>>();
-            let x = coords[0].parse().unwrap();
-            let y = coords[1].parse().unwrap();
-            let z = coords[2].parse().unwrap();
-            (x, y, z)
-        })
-        .collect()
+    data.lines().map(parse_cube).collect()
 }
 
 fn solve_part1(data: &[Cube]) -> usize {
@@ -112,6 +113,52 @@ fn solve_part2(data: &[Cube]) -> usize {
     exposed
 }
 
-generate_main!();
+// cubes are parsed line by line, input text is never held as a whole
+fn stream_input(
+    reader: &mut dyn BufRead,
+    report: &mut dyn FnMut(utils::StageResult),
+) -> io::Result<()> {
+    let (cubes, elapsed) = timed!(utils::stream::lines(reader)
+        .map(|line| line.map(|line| parse_cube(&line)))
+        .collect::<io::Result<Vec<_>>>()?);
+    report(utils::StageResult::new(utils::Stage::Parse, None, elapsed));
+
+    let (result, elapsed) = timed!(solve_part1(&cubes));
+    report(utils::StageResult::new(
+        utils::Stage::Part(1),
+        Some(result.to_string()),
+        elapsed,
+    ));
+    let (result, elapsed) = timed!(solve_part2(&cubes));
+    report(utils::StageResult::new(
+        utils::Stage::Part(2),
+        Some(result.to_string()),
+        elapsed,
+    ));
+
+    Ok(())
+}
+
+generate_main!(stream = stream_input);
 
 generate_tests!(64, 58);
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    #[test]
+    fn stream_test() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let answers = utils::stream::answers(stream_input, &data_str).unwrap();
+        assert_eq!(answers, ["64", "58"]);
+    }
+
+    #[test]
+    fn stream_variants() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::text::assert_same_on_variants(&data_str, |data| {
+            utils::stream::answers(stream_input, data).unwrap()
+        });
+    }
+}
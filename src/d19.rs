@@ -2,7 +2,7 @@
 use std::collections::{HashSet, VecDeque};
 
 mod utils;
-const DAY_ID: utils::DayIdType = 19;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 19);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Material {
@@ -21,9 +21,19 @@ impl Material {
             Material::Geode => 3,
         }
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Material::Ore => "ore",
+            Material::Clay => "clay",
+            Material::Obsidian => "obsidian",
+            Material::Geode => "geode",
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
+#[allow(clippy::struct_field_names)]
 struct Blueprint {
     pub ore_robot_cost: usize,
     pub clay_robot_cost: usize,
@@ -97,7 +107,7 @@ impl Factory {
             *item += diff;
         } else {
             *item -= diff;
-        };
+        }
     }
 
     pub fn mine(&mut self, material: Material) {
@@ -186,20 +196,35 @@ impl Factory {
 }
 
 fn best_geode_count(blueprint: &Blueprint, minutes: usize) -> usize {
-    let mut best_geodes = 0;
+    geodes_from(&Factory::new(minutes), blueprint, 0)
+}
 
-    let mut state_queue = VecDeque::from([Factory::new(minutes)]);
+// most geodes the factory can end up with, just `known` when it cannot beat that count
+fn geodes_from(start: &Factory, blueprint: &Blueprint, known: usize) -> usize {
+    let mut best_geodes = known;
+
+    let mut state_queue = VecDeque::from([start.clone()]);
 
     let (max_ore_robots, max_clay_robots, max_obsidian_robots) = blueprint.robots_for_geode();
 
     let mut states_seen = HashSet::<([usize; 4], [usize; 4], usize)>::new();
 
     while let Some(mut factory) = state_queue.pop_front() {
+        // out of time budget, best count found so far (or by building greedily) is returned
+        if utils::budget::exhausted() {
+            best_geodes = best_geodes.max(greedy_geodes(start, blueprint));
+            break;
+        }
+
+        // geodes collected by the existing robots alone are reached for sure,
+        // so the count stays meaningful when the budget cuts the search short
+        let guaranteed_geodes = factory.count_stock(Material::Geode)
+            + factory.count_robots(Material::Geode) * factory.minutes_to_go;
+        if guaranteed_geodes > best_geodes {
+            best_geodes = guaranteed_geodes;
+        }
+
         if factory.minutes_to_go == 0 {
-            let geodes = factory.count_stock(Material::Geode);
-            if geodes > best_geodes {
-                best_geodes = geodes;
-            }
             continue;
         }
 
@@ -252,13 +277,106 @@ fn best_geode_count(blueprint: &Blueprint, minutes: usize) -> usize {
     best_geodes
 }
 
-fn solve_part1(data: &[Blueprint]) -> usize {
-    const MINUTES: usize = 24;
+// most geodes collected by building the most advanced robot affordable every minute
+// with the ore and clay robots capped at every possible count,
+// a quick lower bound for searches cut short by the time budget
+fn greedy_geodes(start: &Factory, blueprint: &Blueprint) -> usize {
+    let (max_ore_robots, max_clay_robots, max_obsidian_robots) = blueprint.robots_for_geode();
+    (1..=max_ore_robots)
+        .flat_map(|ore_robots| {
+            (1..=max_clay_robots).map(move |clay_robots| (ore_robots, clay_robots))
+        })
+        .map(|(ore_robots, clay_robots)| {
+            let wanted = |material| match material {
+                Material::Ore => ore_robots,
+                Material::Clay => clay_robots,
+                Material::Obsidian => max_obsidian_robots,
+                Material::Geode => usize::MAX,
+            };
+            let mut factory = start.clone();
+            while factory.minutes_to_go > 0 {
+                let robot = [
+                    Material::Geode,
+                    Material::Obsidian,
+                    Material::Clay,
+                    Material::Ore,
+                ]
+                .into_iter()
+                .find(|material| {
+                    factory.can_afford_robot(*material, blueprint)
+                        && factory.count_robots(*material) < wanted(*material)
+                });
+                factory = factory.step_and_build(robot, blueprint);
+            }
+            factory.count_stock(Material::Geode)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+// robot built each minute (`None` when just mining) on the way to the most geodes,
+// every step is the first one from which the search still finds that many geodes
+fn build_schedule(blueprint: &Blueprint, minutes: usize) -> Vec<(Option<Material>, Factory)> {
+    let mut target = best_geode_count(blueprint, minutes);
+    let mut factory = Factory::new(minutes);
+    let mut schedule = vec![];
+
+    while factory.minutes_to_go > 0 {
+        let candidates = [
+            Some(Material::Geode),
+            Some(Material::Obsidian),
+            Some(Material::Clay),
+            Some(Material::Ore),
+            None,
+        ]
+        .into_iter()
+        .filter(|robot| robot.is_none_or(|robot| factory.can_afford_robot(robot, blueprint)))
+        .map(|robot| (robot, factory.step_and_build(robot, blueprint)))
+        .collect::<Vec<_>>();
+
+        let found = candidates
+            .iter()
+            .find(|(_, next)| geodes_from(next, blueprint, target.saturating_sub(1)) >= target);
+        let (robot, next) = if let Some(step) = found {
+            step.clone()
+        } else {
+            // estimate of the search cannot be reached step by step, follow the best real way
+            let (geodes, step) = candidates
+                .into_iter()
+                .map(|step| (geodes_from(&step.1, blueprint, 0), step))
+                .max_by_key(|(geodes, _)| *geodes)
+                .unwrap();
+            target = geodes;
+            step
+        };
+        schedule.push((robot, next.clone()));
+        factory = next;
+    }
+
+    schedule
+}
 
+// both parts can be changed in REPL with `set minutes=<value>` and `set blueprints=<count>`,
+// part 2 always lasts the extra minutes longer than part 1 (as in d16)
+const MINUTES: usize = 24;
+const PART2_EXTRA_MINUTES: usize = 8;
+const PART2_BLUEPRINTS: usize = 3;
+
+fn minutes(part: u8) -> usize {
+    let minutes = utils::params::get("minutes", MINUTES);
+    if part == 1 {
+        minutes
+    } else {
+        minutes + PART2_EXTRA_MINUTES
+    }
+}
+
+fn solve_part1(data: &[Blueprint]) -> usize {
+    let minutes = minutes(1);
     let mut quality_levels = Vec::with_capacity(data.len());
 
-    for blueprint in data.iter() {
-        quality_levels.push(best_geode_count(blueprint, MINUTES));
+    for blueprint in data {
+        quality_levels.push(best_geode_count(blueprint, minutes));
     }
 
     quality_levels
@@ -269,17 +387,114 @@ fn solve_part1(data: &[Blueprint]) -> usize {
 }
 
 fn solve_part2(data: &[Blueprint]) -> usize {
-    const MINUTES: usize = 32;
-
+    let minutes = minutes(2);
+    let blueprints = utils::params::get("blueprints", PART2_BLUEPRINTS);
     let mut maxes = Vec::with_capacity(data.len());
 
-    for blueprint in data.iter().take(3) {
-        maxes.push(best_geode_count(blueprint, MINUTES));
+    for blueprint in data.iter().take(blueprints) {
+        maxes.push(best_geode_count(blueprint, minutes));
     }
 
     maxes.iter().product()
 }
 
-generate_main_sample!();
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let data = parse_input(data_str);
+    let blueprints = if part == 1 {
+        data.len()
+    } else {
+        utils::params::get("blueprints", PART2_BLUEPRINTS)
+    };
+    let minutes = minutes(part);
+
+    data.iter()
+        .take(blueprints)
+        .enumerate()
+        .map(|(index, blueprint)| {
+            let schedule = build_schedule(blueprint, minutes);
+            let geodes = schedule
+                .last()
+                .map_or(0, |(_, factory)| factory.count_stock(Material::Geode));
+            let mut artifact = utils::explain::Artifact::new(
+                format!("blueprint {}: {geodes} geodes", index + 1),
+                &["minute", "built", "ore", "clay", "obsidian", "geodes"],
+            );
+            for (minute, (robot, factory)) in schedule.iter().enumerate() {
+                let built = robot.map_or("-", Material::name);
+                let stock = |material| {
+                    let robots = factory.count_robots(material);
+                    format!("{} ({robots} robots)", factory.count_stock(material))
+                };
+                artifact.row(&[
+                    &(minute + 1),
+                    &built,
+                    &stock(Material::Ore),
+                    &stock(Material::Clay),
+                    &stock(Material::Obsidian),
+                    &stock(Material::Geode),
+                ]);
+            }
+            artifact
+        })
+        .collect()
+}
+
+generate_main_sample!(explain = explain_input);
 
 generate_tests!(33, 62);
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn explains_build_schedule() {
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 1);
+        assert_eq!(artifacts[0].title, "blueprint 1: 9 geodes");
+        assert_eq!(artifacts[1].title, "blueprint 2: 12 geodes");
+        assert_eq!(artifacts[0].rows.len(), MINUTES);
+    }
+}
+
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_budget_keeps_geodes_found_so_far() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::budget::set_limit(Some(std::time::Duration::from_micros(1)));
+
+        let mut results = vec![];
+        solve_input(&data_str, &[2], &mut |result| results.push(result));
+
+        let part2 = results.last().unwrap();
+        assert!(part2.timed_out);
+        let answer = part2.answer.as_deref().unwrap().parse::<usize>().unwrap();
+        assert!(0 < answer && answer <= 56 * 62, "{answer}");
+    }
+
+    #[test]
+    fn greedy_building_bounds_geodes_from_below() {
+        let data = parse_input(&utils::string_from_sample(DAY_ID));
+        let greedy = data
+            .iter()
+            .map(|blueprint| greedy_geodes(&Factory::new(MINUTES), blueprint))
+            .collect::<Vec<_>>();
+        assert_eq!(greedy, [8, 10]);
+    }
+}
+
+#[cfg(test)]
+mod minutes_tests {
+    use super::*;
+
+    #[test]
+    fn part2_follows_part1_minutes() {
+        assert_eq!((minutes(1), minutes(2)), (24, 32));
+        utils::params::set("minutes", 10);
+        assert_eq!((minutes(1), minutes(2)), (10, 18));
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 2);
+        assert_eq!(artifacts[0].rows.len(), 18);
+    }
+}
@@ -1,6 +1,6 @@
 // #![warn(clippy::pedantic)]
 mod utils;
-const DAY_ID: utils::DayIdType = 20;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 20);
 
 type Number = i64;
 
@@ -24,52 +24,20 @@ fn numbers_to_values(data: &[Number]) -> Vec<Value> {
         .collect::<Vec<_>>()
 }
 
-fn solve_part1(data: &[Number]) -> Number {
-    let mut buffer = numbers_to_values(data);
-    let buffer_len = buffer.len();
-
-    for i in 0..buffer.len() {
-        let (current_index, &value) = buffer
-            .iter()
-            .enumerate()
-            .find(|(_position, v)| v.start_index == i)
-            .unwrap();
-
-        let new_index =
-            (current_index as Number + value.value).rem_euclid(buffer_len as Number - 1) as usize;
-        // println!(
-        //     "[{}] <- moving {} from {current_index} to {new_index}",
-        //     values_to_string(&buffer),
-        //     value.value
-        // );
-
-        buffer.remove(current_index);
-        buffer.insert(new_index, value);
-    }
-
-    let (zero_index, _) = buffer
-        .iter()
-        .enumerate()
-        .find(|(_pos, v)| v.value == 0)
-        .unwrap();
-
-    [1000, 2000, 3000]
+fn values_to_string(values: &[Value]) -> String {
+    values
         .iter()
-        .map(|x| buffer[(zero_index + x) % buffer_len].value)
-        .sum()
+        .map(|v| v.value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-fn solve_part2(data: &[Number]) -> Number {
-    const DECRYPTION_KEY: Number = 811_589_153;
-
+// reference solution - mixes by removing each number and inserting it at new position
+fn mix_reference(data: &[Number], rounds: usize) -> Vec<Number> {
     let mut buffer = numbers_to_values(data);
-    for val in &mut buffer {
-        val.value *= DECRYPTION_KEY;
-    }
-
     let buffer_len = buffer.len();
 
-    for _ in 0..10 {
+    for _ in 0..rounds {
         for i in 0..data.len() {
             let (current_index, &value) = buffer
                 .iter()
@@ -79,29 +47,179 @@ fn solve_part2(data: &[Number]) -> Number {
 
             let new_index = (current_index as Number + value.value)
                 .rem_euclid(buffer_len as Number - 1) as usize;
-            // println!(
-            //     "[{}] <- moving {} from {current_index} to {new_index}",
-            //     values_to_string(&buffer),
-            //     value.value
-            // );
+            trace!(
+                "[{}] <- moving {} from {current_index} to {new_index}",
+                values_to_string(&buffer),
+                value.value
+            );
 
             buffer.remove(current_index);
             buffer.insert(new_index, value);
         }
     }
 
-    let (zero_index, _) = buffer
-        .iter()
-        .enumerate()
-        .find(|(_pos, v)| v.value == 0)
-        .unwrap();
+    buffer.iter().map(|v| v.value).collect()
+}
+
+// numbers (as their start indices) in order of mixing, split into buckets of limited size,
+// so both locating a number and moving it takes about square root of numbers count
+struct Buckets {
+    buckets: Vec<Vec<usize>>,
+    bucket_of: Vec<usize>,
+    bucket_size: usize,
+}
+
+impl Buckets {
+    pub fn new(len: usize) -> Self {
+        let mut buckets = Self {
+            buckets: vec![(0..len).collect()],
+            bucket_of: vec![0; len],
+            bucket_size: len.isqrt().max(1),
+        };
+        buckets.rebuild();
+        buckets
+    }
+
+    fn rebuild(&mut self) {
+        let order = self.to_vec();
+        self.buckets = order
+            .chunks(self.bucket_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+        for (bucket, items) in self.buckets.iter().enumerate() {
+            for &item in items {
+                self.bucket_of[item] = bucket;
+            }
+        }
+    }
+
+    pub fn position(&self, item: usize) -> usize {
+        let bucket = self.bucket_of[item];
+        let before = self.buckets[..bucket].iter().map(Vec::len).sum::<usize>();
+        before
+            + self.buckets[bucket]
+                .iter()
+                .position(|&i| i == item)
+                .unwrap()
+    }
+
+    pub fn remove(&mut self, item: usize) {
+        let bucket = &mut self.buckets[self.bucket_of[item]];
+        let index = bucket.iter().position(|&i| i == item).unwrap();
+        bucket.remove(index);
+    }
+
+    pub fn insert(&mut self, mut index: usize, item: usize) {
+        let mut bucket = 0;
+        while index > self.buckets[bucket].len() {
+            index -= self.buckets[bucket].len();
+            bucket += 1;
+        }
+        self.buckets[bucket].insert(index, item);
+        self.bucket_of[item] = bucket;
+
+        if self.buckets[bucket].len() > 2 * self.bucket_size {
+            self.rebuild();
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.buckets.concat()
+    }
+}
+
+// same mixing as the reference one, with numbers kept in buckets
+fn mix(data: &[Number], rounds: usize) -> Vec<Number> {
+    let mut buckets = Buckets::new(data.len());
+    let modulo = data.len() as Number - 1;
+
+    for _ in 0..rounds {
+        for (i, value) in data.iter().enumerate() {
+            let current_index = buckets.position(i);
+            let new_index = (current_index as Number + value).rem_euclid(modulo) as usize;
+
+            buckets.remove(i);
+            buckets.insert(new_index, i);
+        }
+    }
+
+    buckets.to_vec().into_iter().map(|i| data[i]).collect()
+}
+
+fn grove_coordinates(mixed: &[Number]) -> Number {
+    let zero_index = mixed.iter().position(|value| *value == 0).unwrap();
 
     [1000, 2000, 3000]
         .iter()
-        .map(|x| buffer[(zero_index + x) % buffer_len].value)
+        .map(|x| mixed[(zero_index + x) % mixed.len()])
         .sum()
 }
 
-generate_main!();
+const DECRYPTION_KEY: Number = 811_589_153;
+
+fn decrypted(data: &[Number]) -> Vec<Number> {
+    data.iter().map(|value| value * DECRYPTION_KEY).collect()
+}
+
+fn solve_part1(data: &[Number]) -> Number {
+    grove_coordinates(&mix(data, 1))
+}
+
+fn solve_part2(data: &[Number]) -> Number {
+    grove_coordinates(&mix(&decrypted(data), 10))
+}
+
+// compares results of both parts, inputs contain single zero as puzzle ones do
+fn differential(cases: usize, seed: u64) -> Option<utils::differential::Divergence> {
+    let parts = |mix: fn(&[Number], usize) -> Vec<Number>| {
+        move |input: &str| {
+            let data = parse_input(input);
+            (
+                grove_coordinates(&mix(&data, 1)),
+                grove_coordinates(&mix(&decrypted(&data), 10)),
+            )
+        }
+    };
+
+    utils::differential::first_divergence(
+        cases,
+        seed,
+        |rng| {
+            let len = rng.index(2..=60);
+            let zero = rng.index(0..=len - 1);
+            (0..len)
+                .map(|index| {
+                    let value = if index == zero {
+                        0
+                    } else {
+                        // duplicates and values larger than the count are common in puzzle input
+                        let value = rng.range(1..=150);
+                        if rng.index(0..=1) == 0 {
+                            -value
+                        } else {
+                            value
+                        }
+                    };
+                    value.to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        parts(mix_reference),
+        parts(mix),
+    )
+}
+
+generate_main!(differential = differential);
 
 generate_tests!(3, 1_623_178_306);
+
+#[cfg(test)]
+mod differential_tests {
+    #[test]
+    fn optimized_matches_reference() {
+        if let Some(divergence) = super::differential(500, 20) {
+            panic!("{divergence}");
+        }
+    }
+}
@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 21;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 21);
 
 type Number = i64;
 
@@ -92,7 +92,7 @@ impl MonkeyPack {
                 if is_part2 && name == HUMAN_NAME {
                     buffer.push('x');
                 } else {
-                    buffer.push_str(&format!("{num}"));
+                    buffer.push_str(&num.to_string());
                 }
             }
             Job::Operation(op, left, right) => {
@@ -114,10 +114,13 @@ impl MonkeyPack {
         }
     }
 
-    #[allow(dead_code)]
     pub fn to_string(&self, is_part2: bool) -> String {
+        self.branch_to_string(ROOT_NAME, is_part2)
+    }
+
+    pub fn branch_to_string(&self, name: &str, is_part2: bool) -> String {
         let mut buffer = String::new();
-        self.to_buffer(&mut buffer, ROOT_NAME, is_part2);
+        self.to_buffer(&mut buffer, name, is_part2);
         buffer
     }
 
@@ -143,7 +146,7 @@ impl MonkeyPack {
                 }
                 (false, false) => {
                     let new_value = self.monkey_shout(name);
-                    let mut monkey = self.monkeys.get_mut(name).unwrap();
+                    let monkey = self.monkeys.get_mut(name).unwrap();
                     monkey.job = Job::Number(new_value);
                     true
                 }
@@ -173,30 +176,44 @@ fn parse_input(data: &str) -> Vec<Monkey> {
 
 fn solve_part1(data: &[Monkey]) -> Number {
     let pack = MonkeyPack::from_monkeys(data);
-    // println!("{}", pack.to_string(false));
+    trace!("{}", pack.to_string(false));
     pack.monkey_shout(ROOT_NAME)
 }
 
-fn solve_part2(data: &[Monkey]) -> Number {
+// normalized pack, its branch affected by human and the number the branch has to equal
+fn human_equation(data: &[Monkey]) -> (MonkeyPack, String, Number) {
     let mut pack = MonkeyPack::from_monkeys(data);
 
     pack.normalize();
 
     let (left, right) = pack.root_children_names();
-    let (mut human_branch, result_branch) = if pack.is_human_affected(left) {
+    let (human_branch, result_branch) = if pack.is_human_affected(left) {
         (left, right)
     } else {
         (right, left)
     };
 
-    let mut result = pack.monkey_shout(result_branch);
+    let result = pack.monkey_shout(result_branch);
+    let human_branch = human_branch.to_string();
+    (pack, human_branch, result)
+}
+
+// inverts operations on the way from the branch down to human, returns all the
+// branches passed with numbers they have to equal, the last one is human itself
+fn solve_for_human(pack: &MonkeyPack, human_branch: &str, result: Number) -> Vec<(String, Number)> {
+    let mut human_branch = human_branch;
+    let mut result = result;
+    let mut steps = vec![];
 
     while human_branch != HUMAN_NAME {
+        steps.push((human_branch.to_string(), result));
+
         let m = pack.monkeys.get(human_branch).unwrap();
 
-        let mut buffer = String::new();
-        pack.to_buffer(&mut buffer, human_branch, true);
-        // println!("\t{buffer} == {result}");
+        trace!(
+            "\t{} == {result}",
+            pack.branch_to_string(human_branch, true)
+        );
 
         if let Job::Operation(op, left, right) = &m.job {
             let left_monkey = pack.monkeys.get(left).unwrap();
@@ -243,10 +260,11 @@ fn solve_part2(data: &[Monkey]) -> Number {
                     }
                 }
                 (Job::Operation(_, _, _), Job::Operation(_, _, _)) => {
-                    // println!(
-                    //     "left: {:?}\nright: {:?}",
-                    //     &left_monkey.job, &right_monkey.job
-                    // );
+                    verbose!(
+                        "left: {:?}\nright: {:?}",
+                        &left_monkey.job,
+                        &right_monkey.job
+                    );
                     panic!("Non-normalized pack cannot be solved!")
                 }
             }
@@ -255,9 +273,64 @@ fn solve_part2(data: &[Monkey]) -> Number {
         }
     }
 
-    result
+    steps.push((HUMAN_NAME.to_string(), result));
+    steps
+}
+
+fn solve_part2(data: &[Monkey]) -> Number {
+    let (pack, human_branch, result) = human_equation(data);
+    solve_for_human(&pack, &human_branch, result)
+        .last()
+        .unwrap()
+        .1
+}
+
+fn explain_input(data_str: &str, part: u8) -> Vec<utils::explain::Artifact> {
+    let data = parse_input(data_str);
+    if part == 1 {
+        let pack = MonkeyPack::from_monkeys(&data);
+        let mut expression = utils::explain::Artifact::new("root expression", &["expression"]);
+        expression.row(&[&pack.to_string(false)]);
+        return vec![expression];
+    }
+
+    let (pack, human_branch, result) = human_equation(&data);
+    let mut equation = utils::explain::Artifact::new(
+        format!("equation solved for {HUMAN_NAME} (x)"),
+        &["equation"],
+    );
+    equation.row(&[&format!(
+        "{} = {result}",
+        pack.branch_to_string(&human_branch, true)
+    )]);
+
+    let mut steps = utils::explain::Artifact::new(
+        "operations inverted one by one",
+        &["step", "monkey", "equation"],
+    );
+    for (step, (name, value)) in solve_for_human(&pack, &human_branch, result)
+        .iter()
+        .enumerate()
+    {
+        let branch = pack.branch_to_string(name, true);
+        steps.row(&[&step, name, &format!("{branch} = {value}")]);
+    }
+    vec![equation, steps]
 }
 
-generate_main!();
+generate_main!(explain = explain_input);
 
 generate_tests!(152, 301);
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn explains_equation() {
+        let artifacts = explain_input(&utils::string_from_sample(DAY_ID), 2);
+        assert_eq!(artifacts[0].column("equation"), ["((4+(2*(x-3)))/4) = 150"]);
+        let steps = artifacts[1].column("equation");
+        assert_eq!(steps.last(), Some(&"x = 301"));
+    }
+}
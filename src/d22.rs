@@ -1,7 +1,7 @@
 #![warn(clippy::pedantic)]
 use std::collections::HashMap;
 mod utils;
-const DAY_ID: utils::DayIdType = 22;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 22);
 
 type Clockwise = bool;
 type Coord = usize;
@@ -38,13 +38,13 @@ impl From<&str> for Action {
 }
 
 fn parse_input(data: &str) -> (Board, Path) {
-    let (board_lines, path_line) = data.split_once("\n\n").unwrap();
+    let blocks = utils::text::blocks(data);
+    let (board_lines, path_line) = (&blocks[0], blocks[1][0]);
 
-    let rows = board_lines.lines().count();
-    let cols = board_lines.lines().map(str::len).max().unwrap();
+    let cols = board_lines.iter().map(|line| line.len()).max().unwrap();
 
     let mut spots = Spots::new();
-    for (row, line) in board_lines.lines().enumerate() {
+    for (row, line) in board_lines.iter().enumerate() {
         for (col, ch) in line.chars().enumerate() {
             if ['.', '#'].contains(&ch) {
                 spots.insert((row, col), ch);
@@ -57,7 +57,7 @@ fn parse_input(data: &str) -> (Board, Path) {
     let path = words.iter().copied().map(Into::into).collect();
 
     let mut top_left = (0, 0);
-    for (index, ch) in data.lines().next().unwrap().chars().enumerate() {
+    for (index, ch) in board_lines[0].chars().enumerate() {
         if ch == '.' {
             top_left.1 = index;
             break;
@@ -65,8 +65,7 @@ fn parse_input(data: &str) -> (Board, Path) {
     }
 
     let mut row_wraps = vec![];
-    for row in 0..rows {
-        let line = board_lines.lines().nth(row).unwrap();
+    for (row, line) in board_lines.iter().enumerate() {
         let (min, _) = line
             .chars()
             .enumerate()
@@ -79,7 +78,7 @@ fn parse_input(data: &str) -> (Board, Path) {
             .find(|(_i, ch)| ch != &' ')
             .unwrap();
         let max = line.len() - rev_max - 1;
-        // println!("{min} - {max}");
+        trace!("row {row} wraps: {min} - {max}");
         row_wraps.push((min, max));
     }
 
@@ -92,7 +91,6 @@ fn parse_input(data: &str) -> (Board, Path) {
             .unwrap()
             .0;
 
-        // println!("{col}, min: {min}");
         let max = row_wraps
             .iter()
             .enumerate()
@@ -100,6 +98,7 @@ fn parse_input(data: &str) -> (Board, Path) {
             .find(|(_, wrap)| wrap.0 <= col && col <= wrap.1)
             .unwrap()
             .0;
+        trace!("col {col} wraps: {min} - {max}");
 
         col_wraps.push((min, max));
     }
@@ -198,7 +197,7 @@ impl Board {
         //  ███
         //   f
 
-        // println!("\t{direction:?} from ({row},{col}); min/max {min}/{max}");
+        trace!("\t{direction:?} from ({row},{col})");
         match direction {
             Direction::Left => {
                 if row < SIZE && col == SIZE {
@@ -280,10 +279,10 @@ impl Board {
             } else {
                 self.wrap_simple(coords, direction)
             };
-            // println!(
-            //     "\tcandidate: {candidate:?} - {:?}",
-            //     self.spots.get(&candidate.0)
-            // );
+            trace!(
+                "\tcandidate: {candidate:?} - {:?}",
+                self.spots.get(&candidate.0)
+            );
             match self.spots.get(&candidate.0) {
                 Some('.') => {
                     (coords, direction) = candidate;
@@ -327,7 +326,8 @@ fn traverse_map(board: &Board, path: &Path, cube_wrap: bool) -> usize {
     let mut coords = board.top_left;
     let mut direction = Direction::Right;
 
-    for action in path.iter() {
+    for action in path {
+        let (row, col) = coords;
         match action {
             Action::Move(steps) => {
                 (coords, direction) = board.walk(coords, *steps, direction, cube_wrap);
@@ -335,17 +335,185 @@ fn traverse_map(board: &Board, path: &Path, cube_wrap: bool) -> usize {
             Action::RotateLeft => direction = direction.rotate(false),
             Action::RotateRight => direction = direction.rotate(true),
         }
-        // println!(
-        //     "Move from ({row},{col}) {steps} steps \"{direction:?}\", then turn {}",
-        //     if clockwise { "RIGHT" } else { "LEFT" }
-        // );
-        // println!("\tended at ({row},{col}) ({direction:?})");
+        trace!("{action:?} from ({row},{col})\n\tended at {coords:?} ({direction:?})");
     }
 
     let (row, col) = coords;
     1000 * (row + 1) + 4 * (col + 1) + direction.facing_score()
 }
 
+// unit vector in 3D, used for folding the map into a cube
+type Axis = [i8; 3];
+
+fn negated(axis: Axis) -> Axis {
+    axis.map(|value| -value)
+}
+
+// orientation of a face once folded - its normal and directions of its rows and columns
+#[derive(Debug, Copy, Clone)]
+struct FaceOrientation {
+    normal: Axis,
+    right: Axis,
+    down: Axis,
+}
+
+impl FaceOrientation {
+    // orientation of the face glued to this one in given direction of the map
+    fn folded(self, direction: Direction) -> Self {
+        let Self {
+            normal,
+            right,
+            down,
+        } = self;
+        match direction {
+            Direction::Right => Self {
+                normal: right,
+                right: negated(normal),
+                down,
+            },
+            Direction::Left => Self {
+                normal: negated(right),
+                right: normal,
+                down,
+            },
+            Direction::Down => Self {
+                normal: down,
+                right,
+                down: negated(normal),
+            },
+            Direction::Up => Self {
+                normal: negated(down),
+                right,
+                down: normal,
+            },
+        }
+    }
+}
+
+// map must consist of six equally sized square faces, which fold into a cube
+fn check_cube_net(board_lines: &[&str]) -> Vec<String> {
+    let tiles = board_lines
+        .iter()
+        .map(|line| line.chars().filter(|ch| *ch != ' ').count())
+        .sum::<usize>();
+    let size = (tiles / 6).isqrt();
+    if size == 0 || 6 * size * size != tiles {
+        return vec![format!("{tiles} tiles cannot form six equal square faces")];
+    }
+
+    let mut violations = vec![];
+    let rows = board_lines.len().div_ceil(size);
+    let cols = board_lines
+        .iter()
+        .map(|line| line.len())
+        .max()
+        .unwrap_or_default()
+        .div_ceil(size);
+    let mut faces = vec![];
+    for face_row in 0..rows {
+        for face_col in 0..cols {
+            let filled = board_lines
+                .iter()
+                .skip(face_row * size)
+                .take(size)
+                .flat_map(|line| line.chars().skip(face_col * size).take(size))
+                .filter(|ch| *ch != ' ')
+                .count();
+            if filled == size * size {
+                faces.push((face_row, face_col));
+            } else if filled > 0 {
+                violations.push(format!(
+                    "lines {}-{}, columns {}-{}: {filled} tiles only partially cover face of size {size}",
+                    face_row * size + 1,
+                    face_row * size + size,
+                    face_col * size + 1,
+                    face_col * size + size
+                ));
+            }
+        }
+    }
+    if !violations.is_empty() {
+        return violations;
+    }
+
+    // fold faces one by one starting from the first, every one must end up on different side
+    let mut folded = HashMap::from([(
+        faces[0],
+        FaceOrientation {
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        },
+    )]);
+    let mut queue = vec![faces[0]];
+    while let Some((row, col)) = queue.pop() {
+        let orientation = folded[&(row, col)];
+        for (direction, neighbor) in [
+            (Direction::Right, (Some(row), col.checked_add(1))),
+            (Direction::Left, (Some(row), col.checked_sub(1))),
+            (Direction::Down, (row.checked_add(1), Some(col))),
+            (Direction::Up, (row.checked_sub(1), Some(col))),
+        ] {
+            let (Some(row), Some(col)) = neighbor else {
+                continue;
+            };
+            if faces.contains(&(row, col)) && !folded.contains_key(&(row, col)) {
+                folded.insert((row, col), orientation.folded(direction));
+                queue.push((row, col));
+            }
+        }
+    }
+
+    if folded.len() < faces.len() {
+        violations.push("faces of the map are not connected".to_string());
+    }
+    for (index, face) in faces.iter().enumerate() {
+        let Some(orientation) = folded.get(face) else {
+            continue;
+        };
+        if let Some(other) = faces[..index].iter().find(|other| {
+            folded
+                .get(other)
+                .is_some_and(|o| o.normal == orientation.normal)
+        }) {
+            violations.push(format!(
+                "faces starting at line {}, column {} and line {}, column {} overlap when folded",
+                other.0 * size + 1,
+                other.1 * size + 1,
+                face.0 * size + 1,
+                face.1 * size + 1
+            ));
+        }
+    }
+
+    violations
+}
+
+fn check_input(data: &str) -> Vec<String> {
+    let Some((board_text, path_line)) = data.split_once("\n\n") else {
+        return vec!["missing blank line between map and path".to_string()];
+    };
+    let board_lines = board_text.lines().collect::<Vec<_>>();
+
+    let mut violations =
+        utils::check::unexpected_chars(&board_lines, |ch| [' ', '.', '#'].contains(&ch));
+    if violations.is_empty() {
+        violations.extend(check_cube_net(&board_lines));
+    }
+
+    let path_line = path_line.trim_end();
+    if path_line.is_empty() {
+        violations.push("path is empty".to_string());
+    }
+    for violation in utils::check::unexpected_chars(&[path_line], |ch| {
+        ch.is_ascii_digit() || ch == 'L' || ch == 'R'
+    }) {
+        violations.push(violation.replacen("line 1", "path", 1));
+    }
+
+    violations
+}
+
 fn solve_part1((board, path): &(Board, Path)) -> usize {
     traverse_map(board, path, false)
 }
@@ -354,7 +522,7 @@ fn solve_part2((board, path): &(Board, Path)) -> usize {
     traverse_map(board, path, true)
 }
 
-generate_main!();
+generate_main!(check = check_input);
 
 // generate_tests!(6032, 5031);
 
@@ -398,11 +566,22 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let data = utils::string_from_sample(22);
+        let data = utils::string_from_sample(DAY_ID);
         let input = parse_input(&data);
         assert_eq!(6032, solve_part1(&input));
     }
 
+    #[test]
+    fn test_check_cube_net() {
+        let data = utils::string_from_sample(DAY_ID);
+        assert!(check_input(&data).is_empty());
+
+        // six faces in a row wrap around the cube twice
+        assert_eq!(2, check_input("......\n\n1\n").len());
+        // cross folds fine
+        assert!(check_input(" .\n...\n .\n .\n\n1\n").is_empty());
+    }
+
     #[test]
     fn test_p2_cube_wraps() {
         for start in FACE_CORNERS {
@@ -2,7 +2,7 @@
 use std::collections::{HashMap, HashSet};
 
 mod utils;
-const DAY_ID: utils::DayIdType = 23;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 23);
 
 type Coord = i64;
 type Coords = (Coord, Coord);
@@ -38,13 +38,13 @@ impl Elves {
         }
     }
 
-    #[allow(dead_code)]
-    pub fn print(&self) {
+    pub fn render(&self) -> String {
         let min_y = self.elves.iter().map(|elf| elf.0).min().unwrap();
         let max_y = self.elves.iter().map(|elf| elf.0).max().unwrap();
         let min_x = self.elves.iter().map(|elf| elf.1).min().unwrap();
         let max_x = self.elves.iter().map(|elf| elf.1).max().unwrap();
-        // println!("({min_y}~{max_y},{min_x}~{max_x})");
+        trace!("({min_y}~{max_y},{min_x}~{max_x})");
+        let mut picture = String::new();
         for row in min_y..=max_y {
             for col in min_x..=max_x {
                 let ch = if self.elves.contains(&(row, col)) {
@@ -52,10 +52,11 @@ impl Elves {
                 } else {
                     '.'
                 };
-                print!("{ch}");
+                picture.push(ch);
             }
-            println!();
+            picture.push('\n');
         }
+        picture
     }
 
     pub fn count_empties(&self) -> usize {
@@ -98,7 +99,7 @@ impl Elves {
             for (directions, (dy, dx)) in &self.intent_order {
                 if directions.iter().all(|n| !neighbors.contains(n)) {
                     let target = (elf.0 + dy, elf.1 + dx);
-                    // println!("{elf:?} could move to ({target:?})");
+                    trace!("{elf:?} could move to ({target:?})");
                     *intents.entry(target).or_insert(0) += 1;
                     want_to_move.insert(*elf, target);
                     break;
@@ -125,6 +126,53 @@ impl Elves {
     }
 }
 
+// rounds of scattering, yields number of elves moved in each round
+// and stops after the first round in which nobody moved
+struct Rounds {
+    elves: Elves,
+    round: usize,
+    elves_moved: Option<usize>,
+}
+
+impl Rounds {
+    pub fn new(elves: &[Coords]) -> Self {
+        Self {
+            elves: Elves::from_coords(elves),
+            round: 0,
+            elves_moved: None,
+        }
+    }
+}
+
+impl Iterator for Rounds {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.elves_moved == Some(0) {
+            return None;
+        }
+        let elves_moved = self.elves.scatter();
+        self.round += 1;
+        self.elves_moved = Some(elves_moved);
+        Some(elves_moved)
+    }
+}
+
+impl utils::step::Inspect for Rounds {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            utils::step::value("round", self.round),
+            utils::step::value("elves", self.elves.elves.len()),
+            utils::step::value("moved", self.elves_moved.unwrap_or_default()),
+            utils::step::value("empties", self.elves.count_empties()),
+        ]
+    }
+
+    fn render(&self) -> String {
+        self.elves.render()
+    }
+}
+
 type Data = (usize, Vec<Coords>);
 
 fn parse_input(data: &str) -> Data {
@@ -142,26 +190,54 @@ fn parse_input(data: &str) -> Data {
 }
 
 fn solve_part1((_dimensions, elves): &Data) -> usize {
-    let mut elves = Elves::from_coords(elves);
-
-    for _ in 0..10 {
-        elves.scatter();
-    }
+    let mut rounds = Rounds::new(elves);
+    rounds.by_ref().take(10).for_each(drop);
 
-    elves.count_empties()
+    rounds.elves.count_empties()
 }
 
 fn solve_part2((_dimensions, elves): &Data) -> usize {
-    let mut elves = Elves::from_coords(elves);
-
-    let mut rounds = 1;
-    while elves.scatter() > 0 {
-        rounds += 1;
-    }
+    Rounds::new(elves).count()
+}
 
-    rounds
+fn step_through(data_str: &str, _part: u8) {
+    let (_dimensions, elves) = parse_input(data_str);
+    utils::step::prompt(Rounds::new(&elves));
 }
 
-generate_main!();
+generate_main!(step = step_through);
 
 generate_tests!(110, 20);
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    #[test]
+    fn elves_after_ten_rounds() {
+        let (_dimensions, elves) = parse_input(&utils::string_from_sample(DAY_ID));
+        let mut rounds = Rounds::new(&elves);
+        rounds.by_ref().take(10).for_each(drop);
+        utils::snapshot::assert_snapshot(DAY_ID, "elves-round10", &rounds.elves.render());
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let (_dimensions, elves) = parse_input(&utils::string_from_sample(DAY_ID));
+        let output = utils::step::run_script(Rounds::new(&elves), &["n 10"]);
+        assert!(
+            output.contains("round=10, elves=22, moved=9, empties=110"),
+            "{output}"
+        );
+        let output = utils::step::run_script(Rounds::new(&elves), &["n 100"]);
+        assert!(
+            output.contains("simulation finished after 20 steps"),
+            "{output}"
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use std::collections::HashSet;
 
 mod utils;
-const DAY_ID: utils::DayIdType = 24;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 24);
 
 type Coord = usize;
 type Coords = (Coord, Coord);
@@ -85,11 +85,14 @@ impl Maze {
         (self.rows - 1, self.cols - 2)
     }
 
-    #[allow(dead_code)]
-    pub fn print(&self) {
+    // expedition positions are drawn as `E`
+    pub fn render(&self, expedition: &HashSet<Coords>) -> String {
+        let mut picture = String::new();
         for row in 0..self.rows {
             for col in 0..self.cols {
-                let ch = if (row, col) == Self::start() || (row, col) == self.finish() {
+                let ch = if expedition.contains(&(row, col)) {
+                    'E'
+                } else if (row, col) == Self::start() || (row, col) == self.finish() {
                     ' '
                 } else if row == 0 || row == self.rows - 1 || col == 0 || col == self.cols - 1 {
                     '#'
@@ -100,10 +103,11 @@ impl Maze {
                         x => x.to_string().chars().next().unwrap(),
                     }
                 };
-                print!("{ch}");
+                picture.push(ch);
             }
-            println!();
+            picture.push('\n');
         }
+        picture
     }
 
     fn wrapped_coords(&self, (row, col): Coords) -> Coords {
@@ -214,46 +218,225 @@ fn parse_input(data: &str) -> &str {
     data
 }
 
-fn flood_maze(maze: &mut Maze, from: Coords, to: Coords) -> usize {
-    let mut currently_reached = HashSet::<Coords>::from([from]);
+// expedition walking through the valley minute by minute, flooding all reachable
+// positions; every leg starts at the place where the previous one finished
+struct Expedition {
+    maze: Maze,
+    legs: Vec<(Coords, Coords)>,
+    leg: usize,
+    reached: HashSet<Coords>,
+    time: usize,
+}
 
-    let mut time = 0;
-    loop {
-        if currently_reached.contains(&to) {
-            return time;
+impl Expedition {
+    pub fn new(maze: Maze, legs: Vec<(Coords, Coords)>) -> Self {
+        let reached = HashSet::from([legs[0].0]);
+        Self {
+            maze,
+            legs,
+            leg: 0,
+            reached,
+            time: 0,
         }
+    }
+
+    pub fn there_and_back(input: &str, trips: usize) -> Self {
+        let maze = Maze::from_str(input);
+        let (from, to) = (Maze::start(), maze.finish());
+        let legs = (0..trips)
+            .map(|trip| {
+                if trip % 2 == 0 {
+                    (from, to)
+                } else {
+                    (to, from)
+                }
+            })
+            .collect();
+        Self::new(maze, legs)
+    }
+}
+
+impl Iterator for Expedition {
+    // number of positions reached after the minute
+    type Item = usize;
 
-        maze.move_blizzards();
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, to) = *self.legs.get(self.leg)?;
+
+        self.maze.move_blizzards();
 
         let mut next_reached = HashSet::<Coords>::new();
-        for pos in currently_reached {
-            for m in maze.available_moves_at(pos) {
+        for pos in &self.reached {
+            for m in self.maze.available_moves_at(*pos) {
                 next_reached.insert(m);
             }
         }
 
-        currently_reached = next_reached;
-        time += 1;
+        self.reached = next_reached;
+        self.time += 1;
+        if self.reached.contains(&to) {
+            self.reached = HashSet::from([to]);
+            self.leg += 1;
+        }
+        Some(self.reached.len())
+    }
+}
+
+impl utils::step::Inspect for Expedition {
+    fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            utils::step::value("time", self.time),
+            utils::step::value("legs", self.leg),
+            utils::step::value("reached", self.reached.len()),
+        ]
+    }
+
+    fn render(&self) -> String {
+        self.maze.render(&self.reached)
     }
 }
 
 fn solve_part1(input: &str) -> usize {
-    let mut maze = Maze::from_str(input);
-    let from = Maze::start();
-    let to = maze.finish();
-    flood_maze(&mut maze, from, to)
+    Expedition::there_and_back(input, 1).count()
 }
 
 fn solve_part2(input: &str) -> usize {
-    let mut maze = Maze::from_str(input);
-    let from = Maze::start();
-    let to = maze.finish();
-    let path1 = flood_maze(&mut maze, from, to);
-    let path2 = flood_maze(&mut maze, to, from);
-    let path3 = flood_maze(&mut maze, from, to);
-    path1 + path2 + path3
+    Expedition::there_and_back(input, 3).count()
+}
+
+fn step_through(data_str: &str, part: u8) {
+    let trips = if part == 1 { 1 } else { 3 };
+    utils::step::prompt(Expedition::there_and_back(parse_input(data_str), trips));
+}
+
+// basin is surrounded by walls with openings at start and finish only, blizzards
+// never go through the openings as they would not wrap to the other side
+fn check_input(data: &str) -> Vec<String> {
+    let lines = data.lines().collect::<Vec<_>>();
+    let mut violations = utils::check::uneven_lines(&lines);
+    violations.extend(utils::check::unexpected_chars(&lines, |ch| {
+        "#.<>^v".contains(ch)
+    }));
+    if !violations.is_empty() {
+        return violations;
+    }
+    if lines.len() < 3 || lines[0].len() < 3 {
+        return vec!["basin is too small to have walls around".to_string()];
+    }
+
+    let (rows, cols) = (lines.len(), lines[0].len());
+    let openings = [Maze::start(), (rows - 1, cols - 2)];
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            if (col == 1 || col == cols - 2) && (ch == '^' || ch == 'v') {
+                violations.push(format!(
+                    "line {}, column {}: vertical blizzard would leave through opening",
+                    row + 1,
+                    col + 1
+                ));
+            }
+
+            let border = row == 0 || row == rows - 1 || col == 0 || col == cols - 1;
+            let expected = if openings.contains(&(row, col)) {
+                '.'
+            } else if border {
+                '#'
+            } else if ch == '#' {
+                violations.push(format!(
+                    "line {}, column {}: unexpected wall inside basin",
+                    row + 1,
+                    col + 1
+                ));
+                continue;
+            } else {
+                continue;
+            };
+            if ch != expected {
+                violations.push(format!(
+                    "line {}, column {}: expected `{expected}`, found `{ch}`",
+                    row + 1,
+                    col + 1
+                ));
+            }
+        }
+    }
+
+    violations
 }
 
-generate_main!();
+generate_main!(step = step_through, check = check_input);
 
 generate_tests!(18, 18 + 23 + 13);
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use utils::step::Inspect;
+
+    #[test]
+    fn basin() {
+        let sample = utils::string_from_sample(DAY_ID);
+        let mut expedition = Expedition::there_and_back(&sample, 1);
+        utils::snapshot::assert_snapshot(DAY_ID, "basin-start", &expedition.render());
+        expedition.by_ref().for_each(drop);
+        utils::snapshot::assert_snapshot(DAY_ID, "basin-part1", &expedition.render());
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use super::*;
+
+    #[test]
+    fn sample_steps() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let output = utils::step::run_script(
+            Expedition::there_and_back(parse_input(&data_str), 1),
+            &["n 100"],
+        );
+        assert!(output.contains("time=18, legs=1"), "{output}");
+        let output = utils::step::run_script(
+            Expedition::there_and_back(parse_input(&data_str), 3),
+            &["until legs >= 2"],
+        );
+        assert!(output.contains("time=41, legs=2"), "{output}");
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_valid() {
+        assert!(check_input(&utils::string_from_sample(DAY_ID)).is_empty());
+    }
+
+    #[test]
+    fn violations() {
+        let sample = utils::string_from_sample(DAY_ID);
+        assert_eq!(
+            check_input(&sample.replacen("#.<..<<#", "#.<.#<<#", 1)),
+            ["line 3, column 5: unexpected wall inside basin"]
+        );
+        assert_eq!(
+            check_input(&sample.replacen("#.######", "#v######", 1)),
+            [
+                "line 1, column 2: vertical blizzard would leave through opening",
+                "line 1, column 2: expected `.`, found `v`",
+            ]
+        );
+        assert_eq!(
+            check_input(&sample.replacen("######.#", "########", 1)),
+            ["line 6, column 7: expected `.`, found `#`"]
+        );
+        assert_eq!(
+            check_input("#.#\n#.#"),
+            ["basin is too small to have walls around"]
+        );
+        assert_eq!(
+            check_input(&sample.replacen('>', "x", 1)),
+            ["line 2, column 2: unexpected `x`"]
+        );
+    }
+}
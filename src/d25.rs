@@ -1,6 +1,8 @@
 // #![warn(clippy::pedantic)]
+use std::io::{self, BufRead};
+
 mod utils;
-const DAY_ID: utils::DayIdType = 25;
+const DAY_ID: utils::DayId = utils::DayId::new(2022, 25);
 
 fn parse_input(data: &str) -> Vec<String> {
     data.lines().map(Into::into).collect()
@@ -61,6 +63,56 @@ fn solve_part2(_data: &[String]) -> String {
     "Merry X-MAS!".to_string()
 }
 
-generate_main!();
+// numbers are summed up line by line, input text is never held as a whole
+fn stream_input(
+    reader: &mut dyn BufRead,
+    report: &mut dyn FnMut(utils::StageResult),
+) -> io::Result<()> {
+    let (decimal, elapsed) = timed!({
+        let mut decimal: Number = 0;
+        for line in utils::stream::lines(reader) {
+            decimal += snafu_to_decimal(&line?);
+        }
+        decimal
+    });
+    report(utils::StageResult::new(utils::Stage::Parse, None, elapsed));
+
+    let (result, elapsed) = timed!(decimal_to_snafu(decimal));
+    report(utils::StageResult::new(
+        utils::Stage::Part(1),
+        Some(result),
+        elapsed,
+    ));
+    let (result, elapsed) = timed!(solve_part2(&[]));
+    report(utils::StageResult::new(
+        utils::Stage::Part(2),
+        Some(result),
+        elapsed,
+    ));
+
+    Ok(())
+}
+
+generate_main!(stream = stream_input);
 
 generate_tests!("2=-1=0", "Merry X-MAS!");
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    #[test]
+    fn stream_test() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        let answers = utils::stream::answers(stream_input, &data_str).unwrap();
+        assert_eq!(answers, ["2=-1=0", "Merry X-MAS!"]);
+    }
+
+    #[test]
+    fn stream_variants() {
+        let data_str = utils::string_from_sample(DAY_ID);
+        utils::text::assert_same_on_variants(&data_str, |data| {
+            utils::stream::answers(stream_input, data).unwrap()
+        });
+    }
+}
@@ -1,57 +1,166 @@
-use std::fs::read_to_string;
+use std::fmt;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
 
-pub type DayIdType = u8;
+pub mod answers;
+pub mod budget;
+pub mod cache;
+pub mod check;
+pub mod differential;
+pub mod explain;
+pub mod interval;
+pub mod params;
+pub mod repl;
+pub mod runner;
+pub mod server;
+pub mod snapshot;
+pub mod step;
+pub mod stream;
+pub mod text;
+pub mod trace;
+
+#[allow(unused_imports)]
+pub use runner::{run, Answer, Day, Profile, Stage, StageResult};
+
+pub type Year = u16;
+pub type DayNumber = u8;
+
+// puzzle identifier - single event year and its day
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DayId {
+    pub year: Year,
+    pub day: DayNumber,
+}
+
+impl DayId {
+    pub const fn new(year: Year, day: DayNumber) -> Self {
+        Self { year, day }
+    }
+
+    pub fn input_dir(self) -> PathBuf {
+        PathBuf::from(format!("inputs/{}", self.year))
+    }
+
+    pub fn input_path(self) -> PathBuf {
+        self.input_dir().join(format!("{:02}.txt", self.day))
+    }
+
+    pub fn sample_path(self) -> PathBuf {
+        self.input_dir().join(format!("{:02}-sample.txt", self.day))
+    }
+
+    // personal input of a named profile - `inputs/<year>/<profile>/<day>.txt`
+    pub fn profile_input_path(self, profile: &str) -> PathBuf {
+        self.input_dir()
+            .join(profile)
+            .join(format!("{:02}.txt", self.day))
+    }
+
+    // names of all profiles having an input for this day, sorted
+    pub fn profiles(self) -> Vec<String> {
+        let Ok(entries) = read_dir(self.input_dir()) else {
+            return vec![];
+        };
+        let mut profiles = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| self.profile_input_path(name).is_file())
+            .collect::<Vec<_>>();
+        profiles.sort();
+        profiles
+    }
+}
+
+impl fmt::Display for DayId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {:02}", self.year, self.day)
+    }
+}
 
 #[allow(dead_code)]
-pub fn string_from_input(day: DayIdType) -> String {
-    let path = format!("inputs/{:02}.txt", day);
-    read_to_string(path).unwrap()
+pub fn string_from_input(day: DayId) -> String {
+    text::normalize(&read_to_string(day.input_path()).unwrap())
 }
 
 #[allow(dead_code)]
-pub fn string_from_sample(day: DayIdType) -> String {
-    let path = format!("inputs/{:02}-sample.txt", day);
-    read_to_string(path).unwrap()
+pub fn string_from_sample(day: DayId) -> String {
+    text::normalize(&read_to_string(day.sample_path()).unwrap())
 }
 
 #[macro_export]
-macro_rules! print_timed_result {
-    ( $prefix:literal, $expression:expr ) => {
+macro_rules! timed {
+    ( $expression:expr ) => {{
         let start = std::time::Instant::now();
         let result = $expression;
-        let duration = start.elapsed();
-        println!("{}: {:?} ({:?})", $prefix, result, duration);
-    };
+        (result, start.elapsed())
+    }};
 }
 
 #[macro_export]
 macro_rules! generate_main_input {
-    ( $is_sample:expr ) => {
+    ( $is_sample:expr $(, $hook:ident = $function:expr)* ) => {
+        fn solve_input(
+            data_str: &str,
+            parts: &[u8],
+            report: &mut dyn FnMut(utils::StageResult),
+        ) {
+            let (data, elapsed) = timed!(parse_input(data_str));
+            report(utils::StageResult::new(utils::Stage::Parse, None, elapsed));
+            if parts.contains(&1) {
+                utils::budget::start();
+                let (result, elapsed) = timed!(solve_part1(&data));
+                report(utils::StageResult {
+                    timed_out: utils::budget::timed_out(),
+                    ..utils::StageResult::solved(utils::Stage::Part(1), result, elapsed)
+                });
+            }
+            if parts.contains(&2) {
+                utils::budget::start();
+                let (result, elapsed) = timed!(solve_part2(&data));
+                report(utils::StageResult {
+                    timed_out: utils::budget::timed_out(),
+                    ..utils::StageResult::solved(utils::Stage::Part(2), result, elapsed)
+                });
+            }
+        }
+
+        fn repl_input(data_str: &str) {
+            let (data, elapsed) = timed!(parse_input(data_str));
+            utils::repl::prompt(
+                DAY_ID,
+                data_str,
+                elapsed,
+                &data,
+                [
+                    &|data| utils::repl::answer(solve_part1(data)),
+                    &|data| utils::repl::answer(solve_part2(data)),
+                ],
+            );
+        }
+
         fn main() {
-            let data_str = if $is_sample {
-                utils::string_from_sample(DAY_ID)
-            } else {
-                utils::string_from_input(DAY_ID)
-            };
-            let data;
-            print_timed_result!("parse input", data = parse_input(&data_str));
-            print_timed_result!("part 1", solve_part1(&data));
-            print_timed_result!("part 2", solve_part2(&data));
+            utils::run(&utils::Day {
+                repl: Some(repl_input),
+                $($hook: Some($function),)*
+                version: source_version!(),
+                ..utils::Day::new(DAY_ID, $is_sample, solve_input)
+            });
         }
     };
 }
 
 #[macro_export]
 macro_rules! generate_main {
-    () => {
-        generate_main_input!(false);
+    ( $($hook:ident = $function:expr),* $(,)? ) => {
+        generate_main_input!(false $(, $hook = $function)*);
     };
 }
 
 #[macro_export]
 macro_rules! generate_main_sample {
-    () => {
-        generate_main_input!(true);
+    ( $($hook:ident = $function:expr),* $(,)? ) => {
+        generate_main_input!(true $(, $hook = $function)*);
     };
 }
 
@@ -108,3 +217,21 @@ macro_rules! generate_tests {
 //         self.vec[index] = value;
 //     }
 // }
+
+#[cfg(test)]
+mod day_id_tests {
+    use super::*;
+
+    #[test]
+    fn paths_by_year() {
+        let id = DayId::new(2021, 7);
+        assert_eq!(id.input_path(), PathBuf::from("inputs/2021/07.txt"));
+        assert_eq!(id.sample_path(), PathBuf::from("inputs/2021/07-sample.txt"));
+        assert_eq!(
+            id.profile_input_path("alice"),
+            PathBuf::from("inputs/2021/alice/07.txt")
+        );
+        assert_eq!(id.to_string(), "2021 day 07");
+        assert!(DayId::new(2021, 25) < DayId::new(2022, 1));
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use super::{DayId, DayNumber, Year};
+
+// Known (accepted) answers of a single event year, stored in `inputs/<year>/answers.txt`
+// (or `inputs/<year>/<profile>/answers.txt` for named input profiles)
+// one answer per line as `<day>.<part> <answer>`, e.g. `01.2 45000`
+#[derive(Debug, Clone, Default)]
+pub struct AnswerRegistry {
+    year: Year,
+    answers: HashMap<(DayNumber, u8), String>,
+}
+
+impl AnswerRegistry {
+    pub fn path(year: Year) -> PathBuf {
+        PathBuf::from(format!("inputs/{year}/answers.txt"))
+    }
+
+    pub fn profile_path(year: Year, profile: &str) -> PathBuf {
+        PathBuf::from(format!("inputs/{year}/{profile}/answers.txt"))
+    }
+
+    // missing registry file is not an error - there is just nothing to compare against
+    pub fn load_from(year: Year, path: &Path) -> Self {
+        match read_to_string(path) {
+            Ok(text) => Self::parse(year, &text),
+            Err(_) => Self {
+                year,
+                answers: HashMap::new(),
+            },
+        }
+    }
+
+    pub fn parse(year: Year, text: &str) -> Self {
+        let answers = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (key, answer) = line.split_once(' ')?;
+                let (day, part) = key.split_once('.')?;
+                Some((
+                    (day.parse().ok()?, part.parse().ok()?),
+                    answer.trim().to_string(),
+                ))
+            })
+            .collect();
+        Self { year, answers }
+    }
+
+    pub fn get(&self, id: DayId, part: u8) -> Option<&str> {
+        if id.year != self.year {
+            return None;
+        }
+        self.answers.get(&(id.day, part)).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_scoped_to_its_year() {
+        let registry = AnswerRegistry::parse(2022, "# comment\n01.1 24000\n\n01.2 45000\n");
+        assert_eq!(registry.get(DayId::new(2022, 1), 1), Some("24000"));
+        assert_eq!(registry.get(DayId::new(2022, 1), 2), Some("45000"));
+        assert_eq!(registry.get(DayId::new(2022, 2), 1), None);
+        assert_eq!(registry.get(DayId::new(2021, 1), 1), None);
+    }
+
+    #[test]
+    fn registry_paths() {
+        assert_eq!(
+            AnswerRegistry::path(2021),
+            PathBuf::from("inputs/2021/answers.txt")
+        );
+        assert_eq!(
+            AnswerRegistry::profile_path(2022, "alice"),
+            PathBuf::from("inputs/2022/alice/answers.txt")
+        );
+        let missing = AnswerRegistry::load_from(2022, Path::new("inputs/2022/no/such/file"));
+        assert_eq!(missing.get(DayId::new(2022, 1), 1), None);
+    }
+}
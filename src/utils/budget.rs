@@ -0,0 +1,52 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+// time budget of a single part - long-running searches check it periodically
+// and stop with the best answer found so far once it is used up
+thread_local! {
+    static LIMIT: Cell<Option<Duration>> = const { Cell::new(None) };
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static EXHAUSTED: Cell<bool> = const { Cell::new(false) };
+    static CHECKS: Cell<u32> = const { Cell::new(0) };
+}
+
+// clock is read only on every n-th check, searches check it in their innermost loops
+const CHECK_INTERVAL: u32 = 1024;
+
+pub fn set_limit(limit: Option<Duration>) {
+    LIMIT.set(limit);
+}
+
+// limit of the current thread, threads solving on its behalf take it over with `set_limit`
+pub fn limit() -> Option<Duration> {
+    LIMIT.get()
+}
+
+// starts measuring the budget for next part
+pub fn start() {
+    DEADLINE.set(LIMIT.get().map(|limit| Instant::now() + limit));
+    EXHAUSTED.set(false);
+    CHECKS.set(0);
+}
+
+// whether the budget is used up, stays true until next `start`
+#[allow(dead_code)]
+pub fn exhausted() -> bool {
+    if EXHAUSTED.get() {
+        return true;
+    }
+    let Some(deadline) = DEADLINE.get() else {
+        return false;
+    };
+    let checks = CHECKS.get().wrapping_add(1);
+    CHECKS.set(checks);
+    if checks.is_multiple_of(CHECK_INTERVAL) && Instant::now() >= deadline {
+        EXHAUSTED.set(true);
+    }
+    EXHAUSTED.get()
+}
+
+// whether the part finished since last `start` ran out of time
+pub fn timed_out() -> bool {
+    EXHAUSTED.get()
+}
@@ -0,0 +1,192 @@
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::DayId;
+
+pub const DEFAULT_DIR: &str = ".cache/answers";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+// FNV-1a, stable across builds unlike the std hasher
+pub fn input_hash(data: &str) -> u64 {
+    hash_more(FNV_OFFSET, data)
+}
+
+fn hash_more(hash: u64, data: &str) -> u64 {
+    data.bytes().fold(hash, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// sources shared by all the days, any of them can change the answers
+const UTILS_SOURCES: [(&str, &str); 17] = [
+    ("utils.rs", include_str!("../utils.rs")),
+    ("answers.rs", include_str!("answers.rs")),
+    ("budget.rs", include_str!("budget.rs")),
+    ("cache.rs", include_str!("cache.rs")),
+    ("check.rs", include_str!("check.rs")),
+    ("differential.rs", include_str!("differential.rs")),
+    ("explain.rs", include_str!("explain.rs")),
+    ("interval.rs", include_str!("interval.rs")),
+    ("params.rs", include_str!("params.rs")),
+    ("repl.rs", include_str!("repl.rs")),
+    ("runner.rs", include_str!("runner.rs")),
+    ("server.rs", include_str!("server.rs")),
+    ("snapshot.rs", include_str!("snapshot.rs")),
+    ("step.rs", include_str!("step.rs")),
+    ("stream.rs", include_str!("stream.rs")),
+    ("text.rs", include_str!("text.rs")),
+    ("trace.rs", include_str!("trace.rs")),
+];
+
+// hash of all the sources of a solver
+fn sources_hash<'a>(sources: impl IntoIterator<Item = &'a str>) -> u64 {
+    sources.into_iter().fold(FNV_OFFSET, hash_more)
+}
+
+// version of a solver with the given source of its day, see `source_version!`
+pub fn version(day_source: &str) -> u64 {
+    sources_hash(
+        [day_source]
+            .into_iter()
+            .chain(UTILS_SOURCES.map(|(_, source)| source)),
+    )
+}
+
+// version of a solver, hash of the source file of its day and of the shared `utils`
+#[macro_export]
+macro_rules! source_version {
+    () => {
+        $crate::utils::cache::version(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/",
+            file!()
+        )))
+    };
+}
+
+// answer of a part is reusable only for the same input solved by the same solver version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    pub id: DayId,
+    pub part: u8,
+    pub input_hash: u64,
+    pub version: u64,
+}
+
+impl Key {
+    // `<dir>/<year>/<day>-part<part>-<hash>-v<version>.txt`
+    pub fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(self.id.year.to_string()).join(format!(
+            "{:02}-part{}-{:016x}-v{:016x}.txt",
+            self.id.day, self.part, self.input_hash, self.version
+        ))
+    }
+}
+
+// cached answer with the time it originally took to solve
+pub fn load(dir: &Path, key: &Key) -> Option<(String, Duration)> {
+    let content = read_to_string(key.path(dir)).ok()?;
+    let (nanos, answer) = content.split_once('\n')?;
+    let elapsed = Duration::from_nanos(nanos.parse().ok()?);
+    Some((answer.strip_suffix('\n')?.to_string(), elapsed))
+}
+
+pub fn store(dir: &Path, key: &Key, answer: &str, elapsed: Duration) -> io::Result<()> {
+    let path = key.path(dir);
+    create_dir_all(path.parent().unwrap())?;
+    let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+    write(path, format!("{nanos}\n{answer}\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_answer_needs_same_input_and_version() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+        let input = "1000\n2000\n\n3000";
+        let key = Key {
+            id: DayId::new(2022, 1),
+            part: 1,
+            input_hash: input_hash(input),
+            version: 1,
+        };
+        store(&dir, &key, "3000", Duration::from_micros(5)).unwrap();
+
+        let cached = load(&dir, &key);
+        assert_eq!(cached, Some(("3000".to_string(), Duration::from_micros(5))));
+        let changed_input = Key {
+            input_hash: input_hash(&input.replace("1000", "1001")),
+            ..key.clone()
+        };
+        assert_eq!(load(&dir, &changed_input), None);
+        let new_version = Key { version: 2, ..key };
+        assert_eq!(load(&dir, &new_version), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn multiline_answers() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-lines-{}", std::process::id()));
+        let key = Key {
+            id: DayId::new(2022, 10),
+            part: 2,
+            input_hash: input_hash("noop"),
+            version: 1,
+        };
+        store(&dir, &key, "#..\n.#.", Duration::ZERO).unwrap();
+        assert_eq!(
+            load(&dir, &key),
+            Some(("#..\n.#.".to_string(), Duration::ZERO))
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn version_follows_source() {
+        let version = crate::source_version!();
+        assert_eq!(version, crate::source_version!());
+        assert_ne!(version, input_hash(""));
+        assert_ne!(version, self::version("fn main() {}"));
+    }
+
+    #[test]
+    fn utils_change_changes_key() {
+        let day = "fn main() {}";
+        let utils = UTILS_SOURCES.map(|(_, source)| source.to_string());
+        let mut changed = utils.clone();
+        changed[7].push_str("\n// changed");
+        let key = |sources: &[String]| Key {
+            id: DayId::new(2022, 15),
+            part: 2,
+            input_hash: input_hash("1"),
+            version: sources_hash([day].into_iter().chain(sources.iter().map(String::as_str))),
+        };
+        assert_eq!(key(&utils).version, version(day));
+        assert_ne!(key(&utils), key(&changed));
+        assert_ne!(
+            key(&utils).path(Path::new("")),
+            key(&changed).path(Path::new(""))
+        );
+    }
+
+    #[test]
+    fn every_utils_source_is_hashed() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/utils");
+        let mut files = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.push("utils.rs".to_string());
+        files.sort();
+        let mut hashed = UTILS_SOURCES.map(|(file, _)| file.to_string()).to_vec();
+        hashed.sort();
+        assert_eq!(hashed, files);
+    }
+}
@@ -0,0 +1,37 @@
+// only days with input checks use this module
+#![allow(dead_code)]
+
+// violations for lines whose length differs from the first line
+pub fn uneven_lines(lines: &[&str]) -> Vec<String> {
+    let Some(width) = lines.first().map(|line| line.len()) else {
+        return vec!["input is empty".to_string()];
+    };
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.len() != width)
+        .map(|(index, line)| {
+            format!(
+                "line {}: length {} differs from {width} of the first line",
+                index + 1,
+                line.len()
+            )
+        })
+        .collect()
+}
+
+// violations for characters outside of `allowed`, reported with line and column
+pub fn unexpected_chars(lines: &[&str], allowed: impl Fn(char) -> bool) -> Vec<String> {
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, ch)| !allowed(*ch))
+                .map(move |(col, ch)| {
+                    format!("line {}, column {}: unexpected `{ch}`", row + 1, col + 1)
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,112 @@
+// only days with optimized solvers use this module
+#![allow(dead_code)]
+
+use std::fmt::{self, Debug};
+use std::ops::RangeInclusive;
+
+// small deterministic pseudo-random generator (xorshift64*), enough for test inputs
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    // seeds are scrambled by a splitmix64 step so that close seeds give unrelated sequences,
+    // the only seed scrambled to zero state (which would generate zeros forever) is remapped
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Self(if z == 0 { 0x2545_F491_4F6C_DD1D } else { z })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (from, to) = range.into_inner();
+        let span = to.abs_diff(from) + 1;
+        from + i64::try_from(self.next_u64() % span).unwrap()
+    }
+
+    pub fn index(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (from, to) = range.into_inner();
+        let span = u64::try_from(to - from + 1).unwrap();
+        from + usize::try_from(self.next_u64() % span).unwrap()
+    }
+}
+
+// generated input on which reference and optimized solvers disagree
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub case: usize,
+    pub input: String,
+    pub reference: String,
+    pub optimized: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "case {} diverges", self.case)?;
+        writeln!(f, "reference: {}", self.reference)?;
+        writeln!(f, "optimized: {}", self.optimized)?;
+        write!(f, "input:\n{}", self.input.trim_end())
+    }
+}
+
+// runs both solvers on `cases` inputs in puzzle text format, returns the first divergence
+pub fn first_divergence<T: PartialEq + Debug>(
+    cases: usize,
+    seed: u64,
+    generate: impl Fn(&mut Rng) -> String,
+    reference: impl Fn(&str) -> T,
+    optimized: impl Fn(&str) -> T,
+) -> Option<Divergence> {
+    let mut rng = Rng::new(seed);
+    (1..=cases).find_map(|case| {
+        let input = generate(&mut rng);
+        let expected = reference(&input);
+        let actual = optimized(&input);
+        (expected != actual).then(|| Divergence {
+            case,
+            input,
+            reference: format!("{expected:?}"),
+            optimized: format!("{actual:?}"),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_zero_state() {
+        for seed in [
+            0,
+            1,
+            0x9E37_79B9_7F4A_7C15,
+            0x9E37_79B9_7F4A_7C15_u64.wrapping_neg(),
+        ] {
+            let mut rng = Rng::new(seed);
+            assert_ne!(rng.0, 0, "seed {seed:#x}");
+            assert!((0..4).any(|_| rng.next_u64() != 0), "seed {seed:#x}");
+        }
+    }
+
+    #[test]
+    fn deterministic_ranges() {
+        let (mut a, mut b) = (Rng::new(15), Rng::new(15));
+        for _ in 0..100 {
+            let value = a.range(-3..=3);
+            assert_eq!(value, b.range(-3..=3));
+            assert!((-3..=3).contains(&value));
+            assert!((2..=5).contains(&a.index(2..=5)));
+            b.index(2..=5);
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+}
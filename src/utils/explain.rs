@@ -0,0 +1,100 @@
+// only days with `explain` hook use this module
+#![allow(dead_code)]
+
+use std::fmt;
+
+use super::text::json_string;
+
+// table of facts showing how an answer was reached, e.g. path walked or valves opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub title: String,
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Artifact {
+    pub fn new(title: impl Into<String>, columns: &[&'static str]) -> Self {
+        Self {
+            title: title.into(),
+            columns: columns.to_vec(),
+            rows: vec![],
+        }
+    }
+
+    // single row, values in the order of columns
+    pub fn row(&mut self, values: &[&dyn fmt::Display]) {
+        let row = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            row.len(),
+            self.columns.len(),
+            "row does not match columns of `{}`",
+            self.title
+        );
+        self.rows.push(row);
+    }
+
+    // value of the column in all the rows
+    pub fn column(&self, name: &str) -> Vec<&str> {
+        let index = self.columns.iter().position(|column| *column == name);
+        let index = index.unwrap_or_else(|| panic!("no column `{name}` in `{}`", self.title));
+        self.rows.iter().map(|row| row[index].as_str()).collect()
+    }
+}
+
+impl fmt::Display for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.title)?;
+        let widths = (0..self.columns.len())
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .map(|row| row[col].chars().count())
+                    .chain([self.columns[col].len()])
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let header: Vec<String> = self.columns.iter().map(ToString::to_string).collect();
+        for row in [&header].into_iter().chain(&self.rows) {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>();
+            writeln!(f, "  {}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+// `[{"title":..,"rows":[{<column>:<value>,..},..]},..]`
+pub fn to_json(artifacts: &[Artifact]) -> String {
+    let artifacts = artifacts
+        .iter()
+        .map(|artifact| {
+            let rows = artifact
+                .rows
+                .iter()
+                .map(|row| {
+                    let fields = artifact
+                        .columns
+                        .iter()
+                        .zip(row)
+                        .map(|(column, value)| {
+                            format!("{}:{}", json_string(column), json_string(value))
+                        })
+                        .collect::<Vec<_>>();
+                    format!("{{{}}}", fields.join(","))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "{{\"title\":{},\"rows\":[{}]}}",
+                json_string(&artifact.title),
+                rows.join(",")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", artifacts.join(","))
+}
@@ -0,0 +1,215 @@
+// only days working with ranges of integers use this module
+#![allow(dead_code)]
+
+pub type Point = i64;
+
+// closed range of integer points, never empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Interval {
+    pub fn new(start: Point, end: Point) -> Self {
+        assert!(start <= end, "empty interval {start}-{end}");
+        Self { start, end }
+    }
+
+    // count of points, wide enough for the interval of all the points
+    pub fn len(&self) -> u128 {
+        u128::from(self.end.abs_diff(self.start)) + 1
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+}
+
+// union of intervals kept as disjoint, non-adjacent intervals in increasing order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut sorted = iter.into_iter().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(interval.end);
+                }
+                _ => intervals.push(interval),
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // count of points covered
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        *self = self.intervals.iter().copied().chain([interval]).collect();
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.containing(point).is_some()
+    }
+
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        self.containing(interval.start)
+            .is_some_and(|covering| covering.contains_interval(interval))
+    }
+
+    // interval of the set the point lies in
+    fn containing(&self, point: Point) -> Option<&Interval> {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < point);
+        self.intervals
+            .get(index)
+            .filter(|interval| interval.contains(point))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = vec![];
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            intervals.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let intervals = self
+            .intervals
+            .iter()
+            .flat_map(|interval| other.gaps(*interval))
+            .collect();
+        IntervalSet { intervals }
+    }
+
+    // maximal intervals within the bounds not covered by the set, in increasing order
+    pub fn gaps(&self, within: Interval) -> Vec<Interval> {
+        let mut gaps = vec![];
+        let mut next = Some(within.start);
+        for interval in &self.intervals {
+            let Some(start) = next.filter(|start| *start <= within.end) else {
+                break;
+            };
+            if interval.end < start {
+                continue;
+            }
+            if start < interval.start {
+                gaps.push(Interval::new(start, (interval.start - 1).min(within.end)));
+            }
+            next = interval.end.checked_add(1);
+        }
+        if let Some(start) = next.filter(|start| *start <= within.end) {
+            gaps.push(Interval::new(start, within.end));
+        }
+        gaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(Point, Point)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(2, 4), (6, 8), (5, 5), (12, 14)]);
+        assert_eq!(a, set(&[(2, 8), (12, 14)]));
+        let b = set(&[(0, 3), (10, 12)]);
+        assert_eq!(a.union(&b), set(&[(0, 8), (10, 14)]));
+        assert_eq!(a.intersection(&b), set(&[(2, 3), (12, 12)]));
+        assert_eq!(a.difference(&b), set(&[(4, 8), (13, 14)]));
+        assert_eq!(b.difference(&a), set(&[(0, 1), (10, 11)]));
+        assert_eq!(a.len(), 10);
+        assert!(a.contains(5) && !a.contains(9));
+        assert!(a.contains_interval(&Interval::new(3, 8)));
+        assert!(!a.contains_interval(&Interval::new(7, 12)));
+    }
+
+    #[test]
+    fn gaps() {
+        let a = set(&[(2, 8), (12, 14)]);
+        assert_eq!(
+            a.gaps(Interval::new(0, 20)),
+            set(&[(0, 1), (9, 11), (15, 20)]).intervals()
+        );
+        assert_eq!(a.gaps(Interval::new(3, 13)), set(&[(9, 11)]).intervals());
+        assert!(a.gaps(Interval::new(4, 6)).is_empty());
+        let mut a = a;
+        a.insert(Interval::new(9, 11));
+        assert!(a.gaps(Interval::new(2, 14)).is_empty());
+    }
+
+    #[test]
+    fn full_range() {
+        let all = Interval::new(Point::MIN, Point::MAX);
+        assert_eq!(all.len(), 1 << 64);
+        let halves = set(&[(Point::MIN, -1), (0, Point::MAX)]);
+        assert_eq!(halves.intervals(), [all]);
+        assert_eq!(halves.len(), 1 << 64);
+        assert!(halves.gaps(all).is_empty());
+        let ends = set(&[(Point::MIN, Point::MIN), (Point::MAX, Point::MAX)]);
+        assert_eq!(
+            ends.gaps(all),
+            [Interval::new(Point::MIN + 1, Point::MAX - 1)]
+        );
+        assert_eq!(ends.len(), 2);
+    }
+}
@@ -0,0 +1,56 @@
+// only days with tunable constants use this module
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+// named numeric parameters of solutions (e.g. `minutes` of d16), changeable at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub default: usize,
+    pub value: Option<usize>,
+}
+
+// per thread, solvers run on the thread of the session which sets the parameters
+thread_local! {
+    static PARAMS: RefCell<BTreeMap<String, Param>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+// current value of the parameter, every parameter asked for is remembered with its default
+pub fn get(name: &str, default: usize) -> usize {
+    PARAMS.with_borrow_mut(|params| {
+        let param = params.entry(name.to_string()).or_insert(Param {
+            default,
+            value: None,
+        });
+        param.default = default;
+        param.value.unwrap_or(default)
+    })
+}
+
+pub fn set(name: &str, value: usize) {
+    PARAMS.with_borrow_mut(|params| {
+        let param = params.entry(name.to_string()).or_insert(Param {
+            default: value,
+            value: None,
+        });
+        param.value = Some(value);
+    });
+}
+
+pub fn unset(name: &str) -> bool {
+    PARAMS.with_borrow_mut(|params| {
+        params
+            .get_mut(name)
+            .is_some_and(|param| param.value.take().is_some())
+    })
+}
+
+pub fn all() -> Vec<(String, Param)> {
+    PARAMS.with_borrow(|params| {
+        params
+            .iter()
+            .map(|(name, param)| (name.clone(), *param))
+            .collect()
+    })
+}
@@ -0,0 +1,184 @@
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use super::{budget, params, Answer, DayId};
+
+// solution of single part working on already parsed input
+pub type PartSolver<'a, D> = &'a dyn Fn(&D) -> String;
+
+// text of the answer returned by a solver, or of the error instead
+pub fn answer(answer: impl Answer) -> String {
+    answer
+        .into_answer()
+        .unwrap_or_else(|error| format!("error: {error}"))
+}
+
+const HELP: &str = "\
+commands:
+  part1, part2      solve the part on parsed input
+  set <name>=<value>
+                    override parameter of the solution, e.g. `set minutes=30`
+  unset <name>      return parameter to its default
+  show              print input summary, parameters and latest answers
+  stats             print timings of all the runs
+  h, help           print this help
+  q, quit           leave the session";
+
+#[derive(Debug, Default)]
+struct PartStats {
+    runs: u32,
+    total: Duration,
+    fastest: Option<Duration>,
+    last: Option<(String, Duration)>,
+}
+
+impl PartStats {
+    fn record(&mut self, answer: String, elapsed: Duration) {
+        self.runs += 1;
+        self.total += elapsed;
+        self.fastest = Some(self.fastest.map_or(elapsed, |fastest| fastest.min(elapsed)));
+        self.last = Some((answer, elapsed));
+    }
+}
+
+fn parse_assignment(s: &str) -> Result<(&str, usize), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<name>=<value>`, got `{s}`"))?;
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{}` is not a non-negative number", value.trim()))?;
+    Ok((name.trim(), value))
+}
+
+// interactive session on stdin/stdout over input parsed once
+pub fn prompt<D>(
+    id: DayId,
+    data_str: &str,
+    parse_time: Duration,
+    data: &D,
+    parts: [PartSolver<D>; 2],
+) {
+    let stdin = io::stdin();
+    prompt_with(
+        id,
+        data_str,
+        parse_time,
+        data,
+        parts,
+        stdin.lock(),
+        io::stdout(),
+    )
+    .unwrap();
+}
+
+pub fn prompt_with<D>(
+    id: DayId,
+    data_str: &str,
+    parse_time: Duration,
+    data: &D,
+    parts: [PartSolver<D>; 2],
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut stats = [PartStats::default(), PartStats::default()];
+
+    writeln!(output, "{id} input parsed in {parse_time:?}, try `help`")?;
+
+    let mut lines = input.lines();
+    loop {
+        write!(output, "{id}> ")?;
+        output.flush()?;
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let (command, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+
+        match command {
+            "" => {}
+            "part1" | "part2" => {
+                let part = usize::from(command == "part2");
+                budget::start();
+                let start = Instant::now();
+                let result = panic::catch_unwind(AssertUnwindSafe(|| parts[part](data)));
+                let elapsed = start.elapsed();
+                match result {
+                    Ok(answer) => {
+                        let previous = stats[part].last.as_ref().map(|(answer, _)| answer);
+                        let change = match previous {
+                            _ if budget::timed_out() => {
+                                " [timed out, best answer found so far]".to_string()
+                            }
+                            Some(previous) if *previous != answer => {
+                                format!(" [was {previous}]")
+                            }
+                            _ => String::new(),
+                        };
+                        writeln!(output, "part {}: {answer} ({elapsed:?}){change}", part + 1)?;
+                        stats[part].record(answer, elapsed);
+                    }
+                    Err(_) => writeln!(output, "part {} failed", part + 1)?,
+                }
+            }
+            "set" => match parse_assignment(rest) {
+                Ok((name, value)) => params::set(name, value),
+                Err(err) => writeln!(output, "{err}")?,
+            },
+            "unset" => {
+                if !params::unset(rest.trim()) {
+                    writeln!(output, "parameter `{}` is not set", rest.trim())?;
+                }
+            }
+            "show" => {
+                writeln!(
+                    output,
+                    "input: {} lines, {} bytes, parsed in {parse_time:?}",
+                    data_str.lines().count(),
+                    data_str.len()
+                )?;
+                let params = params::all();
+                if params.is_empty() {
+                    writeln!(output, "parameters: none used so far")?;
+                } else {
+                    writeln!(output, "parameters:")?;
+                }
+                for (name, param) in params {
+                    match param.value {
+                        Some(value) => {
+                            writeln!(output, "  {name}={value} (default {})", param.default)?;
+                        }
+                        None => writeln!(output, "  {name}={} (default)", param.default)?,
+                    }
+                }
+                for (part, part_stats) in stats.iter().enumerate() {
+                    if let Some((answer, _)) = &part_stats.last {
+                        writeln!(output, "part {}: {answer}", part + 1)?;
+                    }
+                }
+            }
+            "stats" => {
+                writeln!(output, "parse: {parse_time:?}")?;
+                for (part, part_stats) in stats.iter().enumerate() {
+                    match (&part_stats.last, part_stats.fastest) {
+                        (Some((_, last)), Some(fastest)) => writeln!(
+                            output,
+                            "part {}: {} run(s), last {last:?}, fastest {fastest:?}, mean {:?}",
+                            part + 1,
+                            part_stats.runs,
+                            part_stats.total / part_stats.runs
+                        )?,
+                        _ => writeln!(output, "part {}: not run yet", part + 1)?,
+                    }
+                }
+            }
+            "h" | "help" => writeln!(output, "{HELP}")?,
+            "q" | "quit" => break,
+            _ => writeln!(output, "unknown command `{command}`, try `help`")?,
+        }
+    }
+
+    Ok(())
+}
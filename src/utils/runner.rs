@@ -0,0 +1,906 @@
+use std::fmt;
+use std::fs::{metadata, read_to_string};
+use std::io::{self, BufRead};
+use std::net::TcpListener;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::answers::AnswerRegistry;
+use super::budget;
+use super::cache;
+use super::differential::Divergence;
+use super::explain::{self, Artifact};
+use super::server;
+use super::stream;
+use super::text;
+use super::trace::{self, Level};
+use super::DayId;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Parse,
+    Part(u8),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse input"),
+            Stage::Part(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StageResult {
+    pub stage: Stage,
+    pub answer: Option<String>,
+    pub elapsed: Duration,
+    // answer is just the best one found before time budget ran out
+    pub timed_out: bool,
+    // answer was taken from the answer cache, elapsed is the time of the original run
+    pub cached: bool,
+    // why the part has no answer for the input
+    pub error: Option<String>,
+}
+
+impl StageResult {
+    pub fn new(stage: Stage, answer: Option<String>, elapsed: Duration) -> Self {
+        Self {
+            stage,
+            answer,
+            elapsed,
+            timed_out: false,
+            cached: false,
+            error: None,
+        }
+    }
+
+    // result of the part with the answer returned by its solver, or the error instead
+    pub fn solved(stage: Stage, answer: impl Answer, elapsed: Duration) -> Self {
+        match answer.into_answer() {
+            Ok(answer) => Self::new(stage, Some(answer), elapsed),
+            Err(error) => Self {
+                error: Some(error),
+                ..Self::new(stage, None, elapsed)
+            },
+        }
+    }
+}
+
+// value returned by a solver of a part, text of the answer or of the error why there is none
+pub trait Answer {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+macro_rules! impl_answer {
+    ( $($answer:ty),* ) => {
+        $(
+            impl Answer for $answer {
+                fn into_answer(self) -> Result<String, String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer!(i16, i32, i64, i128, u32, u64, usize, String);
+
+impl<T: fmt::Display, E: fmt::Display> Answer for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map(|answer| answer.to_string())
+            .map_err(|error| error.to_string())
+    }
+}
+
+// parses input text, solves given parts and reports every finished stage as it goes
+pub type Solver = fn(&str, &[u8], &mut dyn FnMut(StageResult));
+
+pub const BOTH_PARTS: &[u8] = &[1, 2];
+
+// loads input text and interactively steps through simulation of given part
+pub type StepHook = fn(&str, u8);
+
+// solves input read incrementally from buffered reader, without holding all of it in memory
+pub type StreamHook = fn(&mut dyn BufRead, &mut dyn FnMut(StageResult)) -> io::Result<()>;
+
+// parses input text once and lets user solve it repeatedly in interactive session
+pub type ReplHook = fn(&str);
+
+// validates input text against structural assumptions of the solution,
+// returns description of every violation found
+pub type CheckHook = fn(&str) -> Vec<String>;
+
+// runs reference and optimized solvers on given count of inputs generated from seed,
+// returns the first input they disagree on
+pub type DifferentialHook = fn(usize, u64) -> Option<Divergence>;
+
+// solves given part of input text, returns artifacts showing how the answer was reached
+pub type ExplainHook = fn(&str, u8) -> Vec<Artifact>;
+
+// describes input text with statistics tables, independently of the answers
+pub type ReportHook = fn(&str) -> Vec<Artifact>;
+
+// plays input text against players given on command line, returns standings and matches
+// or description of the first invalid player
+pub type TournamentHook = fn(&str, &[String]) -> Result<Vec<Artifact>, String>;
+
+// single registered puzzle solution, generated by `generate_main!` macros
+// optional hooks are set as `generate_main!(hook = function)`
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub id: DayId,
+    pub sample_by_default: bool,
+    pub solve: Solver,
+    pub step: Option<StepHook>,
+    pub check: Option<CheckHook>,
+    pub differential: Option<DifferentialHook>,
+    pub repl: Option<ReplHook>,
+    pub stream: Option<StreamHook>,
+    pub explain: Option<ExplainHook>,
+    pub report: Option<ReportHook>,
+    pub tournament: Option<TournamentHook>,
+    // hash of the day's source, every change of the solution invalidates its cached answers
+    pub version: u64,
+}
+
+impl Day {
+    pub fn new(id: DayId, sample_by_default: bool, solve: Solver) -> Self {
+        Self {
+            id,
+            sample_by_default,
+            solve,
+            step: None,
+            check: None,
+            differential: None,
+            repl: None,
+            stream: None,
+            explain: None,
+            report: None,
+            tournament: None,
+            version: 0,
+        }
+    }
+}
+
+// which puzzle input to solve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Profile {
+    Default,
+    Named(String),
+    Sample,
+}
+
+impl Profile {
+    pub fn path(&self, id: DayId) -> PathBuf {
+        match self {
+            Profile::Default => id.input_path(),
+            Profile::Named(name) => id.profile_input_path(name),
+            Profile::Sample => id.sample_path(),
+        }
+    }
+
+    // answers are known for real inputs only
+    pub fn registry_path(&self, id: DayId) -> Option<PathBuf> {
+        match self {
+            Profile::Default => Some(AnswerRegistry::path(id.year)),
+            Profile::Named(name) => Some(AnswerRegistry::profile_path(id.year, name)),
+            Profile::Sample => None,
+        }
+    }
+
+    pub fn registry(&self, id: DayId) -> AnswerRegistry {
+        match self.registry_path(id) {
+            Some(path) => AnswerRegistry::load_from(id.year, &path),
+            None => AnswerRegistry::default(),
+        }
+    }
+
+    // default input followed by all named profiles of the day
+    pub fn all(id: DayId) -> Vec<Profile> {
+        let mut profiles = vec![Profile::Default];
+        profiles.extend(id.profiles().into_iter().map(Profile::Named));
+        profiles
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Profile::Default => write!(f, "(default)"),
+            Profile::Named(name) => write!(f, "{name}"),
+            Profile::Sample => write!(f, "(sample)"),
+        }
+    }
+}
+
+const USAGE: &str = "\
+usage: [command] [--sample | --input | --profile <name> | --all-profiles] [--watch] [--stream] [--seed <n>]
+       [--time-budget <seconds>] [--no-cache] [--json] [--verbose | --trace]
+commands:
+  solve         solve both parts (default)
+  check         validate input against assumptions of the solution
+  step [part]   step through simulation of given part interactively
+  bench [part]  time parsing and given part over repeated runs
+  report        print statistics of the input as tables, or JSON with --json
+  tournament [players]
+                play the input against other players in round-robin tournament
+  explain [part]
+                show how the answer of given part was reached
+  repl          parse input once and solve it in interactive session
+  differential [cases]
+                compare optimized solvers with reference ones on generated inputs
+  serve [address]
+                answer `POST /day/{n}/part/{p}[/explain]` requests with input as body,
+                `-` answers a single request read from stdin";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum Command {
+    #[default]
+    Solve,
+    Check,
+    Step(u8),
+    Explain(u8),
+    Bench(u8),
+    Report,
+    Tournament(Vec<String>),
+    Differential(usize),
+    Serve(String),
+    Repl,
+}
+
+// command line flags are independent switches
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default)]
+struct Args {
+    command: Command,
+    sample: bool,
+    input: bool,
+    profile: Option<String>,
+    all_profiles: bool,
+    watch: bool,
+    stream: bool,
+    no_cache: bool,
+    json: bool,
+    seed: Option<u64>,
+    time_budget: Option<Duration>,
+    trace_level: Level,
+}
+
+impl Args {
+    fn from_env() -> Self {
+        let mut args = Self::default();
+        let mut env_args = std::env::args().skip(1).peekable();
+        while let Some(arg) = env_args.next() {
+            match arg.as_str() {
+                "--sample" => args.sample = true,
+                "--input" => args.input = true,
+                "--profile" => args.profile = env_args.next(),
+                "--all-profiles" => args.all_profiles = true,
+                "--watch" => args.watch = true,
+                "--stream" => args.stream = true,
+                "--no-cache" => args.no_cache = true,
+                "--json" => args.json = true,
+                "--time-budget" => {
+                    let seconds = env_args.next().and_then(|s| s.parse::<f64>().ok());
+                    let budget = seconds.and_then(|s| Duration::try_from_secs_f64(s).ok());
+                    args.time_budget =
+                        Some(budget.unwrap_or_else(|| usage_error("invalid time budget")));
+                }
+                "--seed" => {
+                    let seed = env_args.next().and_then(|seed| seed.parse().ok());
+                    args.seed = Some(seed.unwrap_or_else(|| usage_error("invalid seed")));
+                }
+                "--verbose" => args.trace_level = args.trace_level.max(Level::Verbose),
+                "--trace" => args.trace_level = Level::Trace,
+                "solve" => args.command = Command::Solve,
+                "check" => args.command = Command::Check,
+                "repl" => args.command = Command::Repl,
+                "report" => args.command = Command::Report,
+                "tournament" => {
+                    let mut players = vec![];
+                    while let Some(player) = env_args.next_if(|arg| !arg.starts_with("--")) {
+                        players.push(player);
+                    }
+                    args.command = Command::Tournament(players);
+                }
+                "step" => {
+                    let part = env_args.next_if(|arg| !arg.starts_with("--"));
+                    args.command = Command::Step(parse_part(part.as_deref()));
+                }
+                "bench" => {
+                    let part = env_args.next_if(|arg| !arg.starts_with("--"));
+                    args.command = Command::Bench(parse_part(part.as_deref()));
+                }
+                "explain" => {
+                    let part = env_args.next_if(|arg| !arg.starts_with("--"));
+                    args.command = Command::Explain(parse_part(part.as_deref()));
+                }
+                "differential" => {
+                    let cases = env_args.next_if(|arg| !arg.starts_with("--"));
+                    let cases = cases.map_or(Some(DIFFERENTIAL_CASES), |cases| cases.parse().ok());
+                    args.command = Command::Differential(
+                        cases.unwrap_or_else(|| usage_error("invalid count of cases")),
+                    );
+                }
+                "serve" => {
+                    let address = env_args.next_if(|arg| !arg.starts_with("--"));
+                    args.command =
+                        Command::Serve(address.unwrap_or(server::DEFAULT_ADDRESS.to_string()));
+                }
+                _ => usage_error(&format!("unknown argument: {arg}")),
+            }
+        }
+        if [args.profile.is_some(), args.sample, args.input]
+            .iter()
+            .filter(|selected| **selected)
+            .count()
+            > 1
+        {
+            usage_error("--sample, --input and --profile cannot be combined");
+        }
+        if args.watch && (args.all_profiles || args.command != Command::Solve) {
+            usage_error("--watch works for solving a single input only");
+        }
+        if args.stream && (args.all_profiles || args.watch || args.command != Command::Solve) {
+            usage_error("--stream works for solving a single input only");
+        }
+        if args.json && !matches!(args.command, Command::Report | Command::Tournament(_)) {
+            usage_error("--json works for reports and tournaments only");
+        }
+        args
+    }
+
+    fn profile(&self, day: &Day) -> Profile {
+        match &self.profile {
+            Some(name) => Profile::Named(name.clone()),
+            None if self.input => Profile::Default,
+            None if self.sample || day.sample_by_default => Profile::Sample,
+            None => Profile::Default,
+        }
+    }
+}
+
+const DIFFERENTIAL_CASES: usize = 1000;
+
+// input text with line endings, trailing whitespace and blank lines normalized
+fn read_input(path: &Path) -> io::Result<String> {
+    read_to_string(path).map(|data| text::normalize(&data))
+}
+
+// part number given on command line, first part when missing
+fn parse_part(arg: Option<&str>) -> u8 {
+    match arg {
+        None => 1,
+        Some(part @ ("1" | "2")) => part.parse().unwrap(),
+        Some(arg) => usage_error(&format!("invalid part: {arg}")),
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{message}");
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+pub fn run(day: &Day) {
+    let args = Args::from_env();
+    trace::set_level(args.trace_level);
+    budget::set_limit(args.time_budget);
+
+    match args.command {
+        Command::Solve if args.all_profiles => run_all_profiles(day, !args.no_cache),
+        Command::Solve if args.watch => watch_profile(day, &args.profile(day), !args.no_cache),
+        Command::Solve if args.stream => {
+            let Some(stream) = day.stream else {
+                usage_error(&format!("{} cannot solve streamed input", day.id));
+            };
+            run_profile_streamed(day, stream, &args.profile(day));
+        }
+        Command::Solve => {
+            run_profile(day, &args.profile(day), !args.no_cache);
+        }
+        Command::Check => {
+            let Some(check) = day.check else {
+                usage_error(&format!("{} has no input checks", day.id));
+            };
+            let profiles = if args.all_profiles {
+                Profile::all(day.id)
+            } else {
+                vec![args.profile(day)]
+            };
+            let invalid = profiles
+                .iter()
+                .filter(|profile| !check_profile(day.id, check, profile))
+                .count();
+            if invalid > 0 {
+                std::process::exit(1);
+            }
+        }
+        Command::Step(part) => {
+            let Some(step) = day.step else {
+                usage_error(&format!("{} has no step-through simulation", day.id));
+            };
+            let data_str = read_input(&args.profile(day).path(day.id)).unwrap();
+            step(&data_str, part);
+        }
+        Command::Explain(part) => {
+            let Some(explain) = day.explain else {
+                usage_error(&format!("{} cannot explain its answers", day.id));
+            };
+            let data_str = read_input(&args.profile(day).path(day.id)).unwrap();
+            println!("{} part {part}", day.id);
+            for artifact in explain(&data_str, part) {
+                print!("\n{artifact}");
+            }
+        }
+        Command::Bench(part) => {
+            let path = args.profile(day).path(day.id);
+            let data_str = read_input(&path).unwrap();
+            println!("{} ({})", day.id, path.display());
+            bench_part(day, &data_str, part);
+        }
+        Command::Report => {
+            let Some(report) = day.report else {
+                usage_error(&format!("{} has no input report", day.id));
+            };
+            let data_str = read_input(&args.profile(day).path(day.id)).unwrap();
+            print_report(day, &report(&data_str), args.json);
+        }
+        Command::Tournament(ref players) => run_tournament(day, &args, players),
+        Command::Repl => {
+            let Some(repl) = day.repl else {
+                usage_error(&format!("{} has no interactive session", day.id));
+            };
+            let data_str = read_input(&args.profile(day).path(day.id)).unwrap();
+            repl(&data_str);
+        }
+        Command::Serve(address) => run_server(day, &address),
+        Command::Differential(cases) => {
+            let Some(differential) = day.differential else {
+                usage_error(&format!("{} has no optimized solvers", day.id));
+            };
+            let seed = args.seed.unwrap_or_else(|| {
+                let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
+                now.map_or(0, |now| now.as_secs())
+            });
+            println!("{}: {cases} generated case(s), seed {seed}", day.id);
+            match differential(cases, seed) {
+                None => println!("reference and optimized solvers agree"),
+                Some(divergence) => {
+                    println!("{divergence}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+// report as tables, or as JSON array for other tools
+fn print_report(day: &Day, artifacts: &[Artifact], json: bool) {
+    if json {
+        println!("{}", explain::to_json(artifacts));
+        return;
+    }
+    println!("{}", day.id);
+    for artifact in artifacts {
+        print!("\n{artifact}");
+    }
+}
+
+// `-` answers a single forwarded request on stdin, anything else is the address to listen on
+fn run_server(day: &Day, address: &str) {
+    if address == "-" {
+        server::serve_stdio(*day).unwrap();
+        return;
+    }
+    let registry = server::Registry::with_siblings(*day);
+    let listener = TcpListener::bind(address).unwrap();
+    let days = registry
+        .days()
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>();
+    println!(
+        "{}: answering POST http://{}/day/{{n}}/part/{{1,2}}[/explain] for days {}",
+        day.id.year,
+        listener.local_addr().unwrap(),
+        days.join(", ")
+    );
+    server::serve(&registry, &listener);
+}
+
+fn run_tournament(day: &Day, args: &Args, players: &[String]) {
+    let Some(tournament) = day.tournament else {
+        usage_error(&format!("{} has no tournaments", day.id));
+    };
+    let data_str = read_input(&args.profile(day).path(day.id)).unwrap();
+    match tournament(&data_str, players) {
+        Ok(artifacts) => print_report(day, &artifacts, args.json),
+        Err(message) => {
+            eprintln!("{}: {message}", day.id);
+            std::process::exit(1);
+        }
+    }
+}
+
+// reports answers of the input found in the answer cache (unless it is not used) and solves
+// the other parts, their answers are stored to the cache unless timed out
+fn solve_cached(day: &Day, data_str: &str, use_cache: bool, report: &mut dyn FnMut(StageResult)) {
+    let dir = Path::new(cache::DEFAULT_DIR);
+    let input_hash = cache::input_hash(data_str);
+    let key = |part| cache::Key {
+        id: day.id,
+        part,
+        input_hash,
+        version: day.version,
+    };
+
+    let mut cached = vec![];
+    let mut parts = vec![];
+    for &part in BOTH_PARTS {
+        match cache::load(dir, &key(part)).filter(|_| use_cache) {
+            Some((answer, elapsed)) => cached.push(StageResult {
+                cached: true,
+                ..StageResult::new(Stage::Part(part), Some(answer), elapsed)
+            }),
+            None => parts.push(part),
+        }
+    }
+
+    let mut cached = cached.into_iter().peekable();
+    if !parts.is_empty() {
+        (day.solve)(data_str, &parts, &mut |result| {
+            if let Stage::Part(part) = result.stage {
+                while let Some(earlier) = cached.next_if(|earlier| earlier.stage < result.stage) {
+                    report(earlier);
+                }
+                if let Some(answer) = result.answer.as_deref().filter(|_| !result.timed_out) {
+                    let stored = cache::store(dir, &key(part), answer, result.elapsed);
+                    if let Err(err) = stored {
+                        eprintln!("answer cache not updated: {err}");
+                    }
+                }
+            }
+            report(result);
+        });
+    }
+    cached.for_each(report);
+}
+
+// solves input of the profile, returns all the reported stage results
+fn run_profile(day: &Day, profile: &Profile, use_cache: bool) -> Vec<StageResult> {
+    let path = profile.path(day.id);
+    let registry = profile.registry(day.id);
+
+    println!("{} ({})", day.id, path.display());
+    let data_str = read_input(&path).unwrap();
+
+    let mut results = vec![];
+    solve_cached(day, &data_str, use_cache, &mut |result| {
+        let expected = match result.stage {
+            Stage::Parse => None,
+            Stage::Part(part) => registry.get(day.id, part),
+        };
+        print_stage_result(&result, expected);
+        results.push(result);
+    });
+    results
+}
+
+// solves input of the profile read incrementally by the stream hook of the day
+fn run_profile_streamed(day: &Day, stream: StreamHook, profile: &Profile) {
+    let path = profile.path(day.id);
+    let registry = profile.registry(day.id);
+
+    println!("{} ({}, streamed)", day.id, path.display());
+    let mut reader = stream::open(&path).unwrap();
+
+    stream(&mut reader, &mut |result| {
+        let expected = match result.stage {
+            Stage::Parse => None,
+            Stage::Part(part) => registry.get(day.id, part),
+        };
+        print_stage_result(&result, expected);
+    })
+    .unwrap();
+}
+
+const BENCH_TIME: Duration = Duration::from_secs(1);
+const BENCH_MAX_RUNS: usize = 100;
+
+// solves the part repeatedly until bench time or count of runs is up, at least once
+fn bench_part(day: &Day, data_str: &str, part: u8) {
+    let mut parse_times = vec![];
+    let mut part_times = vec![];
+    let start = Instant::now();
+    while part_times.is_empty()
+        || (start.elapsed() < BENCH_TIME && part_times.len() < BENCH_MAX_RUNS)
+    {
+        (day.solve)(data_str, &[part], &mut |result| match result.stage {
+            Stage::Parse => parse_times.push(result.elapsed),
+            Stage::Part(_) => part_times.push(result.elapsed),
+        });
+    }
+    print_timings(Stage::Parse, &mut parse_times);
+    print_timings(Stage::Part(part), &mut part_times);
+}
+
+fn print_timings(stage: Stage, times: &mut [Duration]) {
+    times.sort();
+    let mean = times.iter().sum::<Duration>() / u32::try_from(times.len()).unwrap();
+    println!(
+        "{stage}: median {:?}, min {:?}, mean {mean:?}, {} run(s)",
+        times[times.len() / 2],
+        times[0],
+        times.len()
+    );
+}
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+// modification time and size of every watched file, `None` for missing ones
+fn files_state(paths: &[PathBuf]) -> Vec<Option<(SystemTime, u64)>> {
+    paths
+        .iter()
+        .map(|path| {
+            let meta = metadata(path).ok()?;
+            Some((meta.modified().ok()?, meta.len()))
+        })
+        .collect()
+}
+
+// detects changes of watched files between polls, the first poll is always a change
+struct FileWatcher {
+    paths: Vec<PathBuf>,
+    last_state: Option<Vec<Option<(SystemTime, u64)>>>,
+}
+
+impl FileWatcher {
+    fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            last_state: None,
+        }
+    }
+
+    fn changed(&mut self) -> bool {
+        let state = files_state(&self.paths);
+        if self.last_state.as_ref() == Some(&state) {
+            return false;
+        }
+        self.last_state = Some(state);
+        true
+    }
+}
+
+// re-runs the day whenever its input or known answers change, until interrupted
+fn watch_profile(day: &Day, profile: &Profile, use_cache: bool) {
+    let mut paths = vec![profile.path(day.id)];
+    paths.extend(profile.registry_path(day.id));
+    let input_path = paths[0].clone();
+    let mut watcher = FileWatcher::new(paths);
+
+    let mut previous: Option<Vec<StageResult>> = None;
+    loop {
+        if watcher.changed() {
+            // input may be half-edited, failed run must not stop watching
+            match panic::catch_unwind(AssertUnwindSafe(|| run_profile(day, profile, use_cache))) {
+                Ok(results) => {
+                    if let Some(previous) = &previous {
+                        println!("changes since previous run:");
+                        for line in answers_diff(previous, &results) {
+                            println!("  {line}");
+                        }
+                    }
+                    previous = Some(results);
+                }
+                Err(_) => println!("run failed, previous answers kept"),
+            }
+            println!("watching {} for changes...", input_path.display());
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+// answer of every part compared with the previous run
+fn answers_diff(previous: &[StageResult], current: &[StageResult]) -> Vec<String> {
+    let answer_of = |results: &[StageResult], stage| {
+        results
+            .iter()
+            .find(|result| result.stage == stage)
+            .and_then(|result| result.answer.clone())
+    };
+
+    BOTH_PARTS
+        .iter()
+        .map(|&part| {
+            let stage = Stage::Part(part);
+            let before = answer_of(previous, stage).unwrap_or_default();
+            let after = answer_of(current, stage).unwrap_or_default();
+            if before == after {
+                format!("{stage}: unchanged")
+            } else {
+                format!("{stage}: {before} -> {after}")
+            }
+        })
+        .collect()
+}
+
+// prints all violations of the input, returns whether input is valid
+fn check_profile(id: DayId, check: CheckHook, profile: &Profile) -> bool {
+    let path = profile.path(id);
+    println!("{id} ({})", path.display());
+
+    let violations = match read_input(&path) {
+        Ok(data_str) => check(&data_str),
+        Err(err) => vec![format!("unreadable input: {err}")],
+    };
+    if violations.is_empty() {
+        println!("input ok");
+    } else {
+        for violation in &violations {
+            println!("  - {violation}");
+        }
+        println!("{} violation(s) found", violations.len());
+    }
+    violations.is_empty()
+}
+
+// solves every input profile of the day and prints answers side by side
+fn run_all_profiles(day: &Day, use_cache: bool) {
+    let mut rows = vec![vec![
+        "profile".to_string(),
+        "part 1".to_string(),
+        "part 2".to_string(),
+        "time".to_string(),
+    ]];
+    let mut mismatches = 0;
+
+    for profile in Profile::all(day.id) {
+        let registry = profile.registry(day.id);
+        let mut row = vec![profile.to_string()];
+        let mut total = Duration::ZERO;
+
+        match read_input(&profile.path(day.id)) {
+            Ok(data_str) => solve_cached(day, &data_str, use_cache, &mut |result| {
+                if !result.cached {
+                    total += result.elapsed;
+                }
+                if let Stage::Part(part) = result.stage {
+                    let expected = registry.get(day.id, part);
+                    if let Some(error) = result.error {
+                        row.push(format!("[error: {error}]"));
+                        return;
+                    }
+                    let answer = result.answer.unwrap_or_default();
+                    if result.timed_out {
+                        row.push(format!("{answer} [timed out]"));
+                        return;
+                    }
+                    if expected.is_some_and(|expected| expected != answer) {
+                        mismatches += 1;
+                    }
+                    let cached = if result.cached { " [cached]" } else { "" };
+                    row.push(format!(
+                        "{answer}{}{cached}",
+                        answer_check(&answer, expected)
+                    ));
+                }
+            }),
+            Err(err) => row.push(format!("unreadable input: {err}")),
+        }
+
+        row.resize(3, String::new());
+        row.push(format!("{total:?}"));
+        rows.push(row);
+    }
+
+    println!("{}", day.id);
+    print_table(&rows);
+    if mismatches > 0 {
+        println!("{mismatches} answer(s) differ from the recorded ones");
+    }
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let widths = (0..columns)
+        .map(|col| {
+            rows.iter()
+                .filter_map(|row| row.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        println!("{}", line.trim_end());
+    }
+}
+
+fn answer_check(answer: &str, expected: Option<&str>) -> String {
+    match expected {
+        Some(expected) if answer == expected => " [ok]".to_string(),
+        Some(expected) => format!(" [expected {expected}]"),
+        None => String::new(),
+    }
+}
+
+// answers spanning more lines (pictures) are printed indented below the stage
+fn print_stage_result(result: &StageResult, expected: Option<&str>) {
+    let answer = result.answer.as_deref().unwrap_or_default();
+    let check = match (&result.answer, &result.error) {
+        (None, Some(error)) => format!(" [error: {error}]"),
+        (Some(_), _) if result.timed_out => " [timed out, best answer found so far]".to_string(),
+        (Some(_), _) => answer_check(answer, expected),
+        (None, None) => String::new(),
+    };
+    let cached = if result.cached { ", cached" } else { "" };
+    if answer.contains('\n') {
+        println!("{}: ({:?}{cached}){check}", result.stage, result.elapsed);
+        for line in answer.lines() {
+            println!("\t{line}");
+        }
+        return;
+    }
+    let separator = if answer.is_empty() { "" } else { " " };
+    println!(
+        "{}:{separator}{answer} ({:?}{cached}){check}",
+        result.stage, result.elapsed
+    );
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use super::*;
+    use std::fs::{remove_file, write};
+
+    #[test]
+    fn detects_changes() {
+        let path = std::env::temp_dir().join(format!("watch-test-{}.txt", std::process::id()));
+        write(&path, "1").unwrap();
+        let mut watcher = FileWatcher::new(vec![path.clone()]);
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        write(&path, "1\n2").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        write(&path, "3").unwrap();
+        assert!(watcher.changed());
+        remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn diff_of_answers() {
+        let result = |part, answer: &str| {
+            StageResult::new(Stage::Part(part), Some(answer.to_string()), Duration::ZERO)
+        };
+        let previous = [result(1, "24000"), result(2, "45000")];
+        let current = [result(1, "24000"), result(2, "41000")];
+        assert_eq!(
+            answers_diff(&previous, &current),
+            ["part 1: unchanged", "part 2: 45000 -> 41000"]
+        );
+        assert_eq!(
+            answers_diff(&[], &current[..1]),
+            ["part 1:  -> 24000", "part 2: unchanged"]
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use super::budget;
+use super::explain;
+use super::runner::{Day, Stage};
+use super::text;
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:2022";
+
+// largest accepted input, real puzzle inputs have tens of kilobytes at most
+const MAX_BODY_LEN: usize = 10 * 1024 * 1024;
+
+// clients silent (or not reading) for longer are dropped
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+// days answered by the server: the one compiled into this binary,
+// the others by their own binaries run with `serve -`
+pub struct Registry {
+    local: Day,
+    binaries: BTreeMap<u8, PathBuf>,
+}
+
+impl Registry {
+    pub fn new(local: Day) -> Self {
+        Self {
+            local,
+            binaries: BTreeMap::new(),
+        }
+    }
+
+    // registers day binaries built next to the running one, as `cargo build` places them
+    pub fn with_siblings(local: Day) -> Self {
+        let mut registry = Self::new(local);
+        let exe = std::env::current_exe().ok();
+        let Some(dir) = exe.as_deref().and_then(|exe| exe.parent()) else {
+            return registry;
+        };
+        for day in 1..=25 {
+            let path = dir.join(format!("d{day:02}{}", std::env::consts::EXE_SUFFIX));
+            if path.is_file() {
+                registry.add_binary(day, path);
+            }
+        }
+        registry
+    }
+
+    pub fn add_binary(&mut self, day: u8, path: PathBuf) {
+        if day != self.local.id.day {
+            self.binaries.insert(day, path);
+        }
+    }
+
+    pub fn days(&self) -> Vec<u8> {
+        let mut days = self.binaries.keys().copied().collect::<Vec<_>>();
+        days.push(self.local.id.day);
+        days.sort_unstable();
+        days
+    }
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", text::json_string(message)),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Content",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+impl Request {
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            self.method,
+            self.path,
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)
+    }
+}
+
+impl Response {
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+
+    fn read_from(stream: &mut impl Read) -> Option<Self> {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).ok()?;
+        let (head, body) = response.split_once("\r\n\r\n")?;
+        let status = head.split_ascii_whitespace().nth(1)?.parse().ok()?;
+        Some(Self {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
+
+fn read_request(stream: &mut impl Read) -> Result<Request, Response> {
+    let bad_request = |_| Response::error(400, "malformed request");
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(bad_request)?;
+    let mut words = line.split_ascii_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).map_err(bad_request)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid content length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_LEN {
+        return Err(Response::error(413, "input too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+
+    Ok(Request { method, path, body })
+}
+
+// `/day/{n}/part/{p}` as (n, p, false), `/day/{n}/part/{p}/explain` as (n, p, true)
+fn parse_path(path: &str) -> Option<(&str, &str, bool)> {
+    let rest = path.strip_prefix("/day/")?;
+    let (day, part) = rest.split_once("/part/")?;
+    match part.strip_suffix("/explain") {
+        Some(part) => Some((day, part, true)),
+        None => Some((day, part, false)),
+    }
+}
+
+fn explain(day: &Day, part: u8, data_str: &str) -> Response {
+    let Some(explain) = day.explain else {
+        return Response::error(404, &format!("{} cannot explain its answers", day.id));
+    };
+    let Ok(artifacts) = panic::catch_unwind(|| explain(data_str, part)) else {
+        return Response::error(500, "solver failed on given input");
+    };
+    Response {
+        status: 200,
+        body: format!(
+            "{{\"year\":{},\"day\":{},\"part\":{part},\"artifacts\":{}}}",
+            day.id.year,
+            day.id.day,
+            explain::to_json(&artifacts)
+        ),
+    }
+}
+
+// passes the request to the binary of another day
+fn forward(binary: &PathBuf, request: &Request) -> Response {
+    let failed = || Response::error(500, "solver of the day failed to answer");
+    let mut command = Command::new(binary);
+    command.args(["serve", "-"]);
+    if let Some(limit) = budget::limit() {
+        command.args(["--time-budget", &limit.as_secs_f64().to_string()]);
+    }
+    let Ok(mut child) = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return failed();
+    };
+    let written = child
+        .stdin
+        .take()
+        .map(|mut stdin| request.write_to(&mut stdin));
+    let response = child
+        .stdout
+        .take()
+        .and_then(|mut stdout| Response::read_from(&mut stdout));
+    let _ = child.wait();
+    match (written, response) {
+        (Some(Ok(())), Some(response)) => response,
+        _ => failed(),
+    }
+}
+
+fn dispatch(registry: &Registry, request: &Request) -> Response {
+    let Some((day_number, _, _)) = parse_path(&request.path) else {
+        return Response::error(404, "expected path /day/{n}/part/{p}[/explain]");
+    };
+    match day_number.parse() {
+        Ok(day) if day == registry.local.id.day => solve(&registry.local, request),
+        Ok(day) if registry.binaries.contains_key(&day) => {
+            forward(&registry.binaries[&day], request)
+        }
+        _ => Response::error(404, &format!("day {day_number} is not served here")),
+    }
+}
+
+fn solve(day: &Day, request: &Request) -> Response {
+    let Some((_, part, explained)) = parse_path(&request.path) else {
+        return Response::error(404, "expected path /day/{n}/part/{p}[/explain]");
+    };
+    if request.method != "POST" {
+        return Response::error(405, "input has to be sent with POST");
+    }
+    let part = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => return Response::error(404, &format!("invalid part {part}")),
+    };
+    let Ok(data_str) = std::str::from_utf8(&request.body) else {
+        return Response::error(400, "input is not valid UTF-8");
+    };
+    let data_str = &text::normalize(data_str);
+    if explained {
+        return explain(day, part, data_str);
+    }
+
+    let mut parse_time = Duration::ZERO;
+    let mut answer = None;
+    let mut part_time = Duration::ZERO;
+    let mut timed_out = false;
+    let mut error = None;
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        (day.solve)(data_str, &[part], &mut |result| match result.stage {
+            Stage::Parse => parse_time = result.elapsed,
+            Stage::Part(_) => {
+                answer = result.answer;
+                part_time = result.elapsed;
+                timed_out = result.timed_out;
+                error = result.error;
+            }
+        });
+    }));
+    if solved.is_err() {
+        return Response::error(500, "solver failed on given input");
+    }
+    if let Some(error) = error {
+        return Response::error(422, &error);
+    }
+
+    let answer = answer
+        .as_deref()
+        .map_or("null".to_string(), text::json_string);
+    Response {
+        status: 200,
+        body: format!(
+            "{{\"year\":{},\"day\":{},\"part\":{part},\"answer\":{answer},\"parse_time_us\":{},\"part_time_us\":{},\"timed_out\":{timed_out}}}",
+            day.id.year,
+            day.id.day,
+            parse_time.as_micros(),
+            part_time.as_micros()
+        ),
+    }
+}
+
+fn handle_connection(registry: &Registry, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let response = match read_request(&mut stream) {
+        Ok(request) => dispatch(registry, &request),
+        Err(response) => response,
+    };
+    response.write_to(&mut stream)
+}
+
+// answers every connection on its own thread, failed connections do not stop the server,
+// the time budget of the calling thread applies to every request
+pub fn serve(registry: &Registry, listener: &TcpListener) {
+    let limit = budget::limit();
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        budget::set_limit(limit);
+                        if let Err(err) = handle_connection(registry, stream) {
+                            eprintln!("connection failed: {err}");
+                        }
+                    });
+                }
+                Err(err) => eprintln!("accepting connection failed: {err}"),
+            }
+        }
+    });
+}
+
+// answers a single request read from stdin, as other days' servers forward them
+pub fn serve_stdio(day: Day) -> io::Result<()> {
+    let response = match read_request(&mut io::stdin().lock()) {
+        Ok(request) => solve(&day, &request),
+        Err(response) => response,
+    };
+    response.write_to(&mut io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::StageResult;
+    use crate::utils::DayId;
+    use std::net::SocketAddr;
+
+    // sums the numbers on the input lines for both parts
+    fn sum_lines(data: &str, parts: &[u8], report: &mut dyn FnMut(StageResult)) {
+        let numbers = data
+            .lines()
+            .map(|line| line.parse::<u64>().unwrap())
+            .collect::<Vec<_>>();
+        report(StageResult::new(Stage::Parse, None, Duration::ZERO));
+        for part in parts {
+            let answer = numbers.iter().sum::<u64>() * u64::from(*part);
+            report(StageResult::new(
+                Stage::Part(*part),
+                Some(answer.to_string()),
+                Duration::ZERO,
+            ));
+        }
+    }
+
+    // counts until the time budget runs out, never finishing without one
+    fn count_until_budget(_data: &str, parts: &[u8], report: &mut dyn FnMut(StageResult)) {
+        report(StageResult::new(Stage::Parse, None, Duration::ZERO));
+        for part in parts {
+            budget::start();
+            let mut count = 0u64;
+            while !budget::exhausted() {
+                count += 1;
+            }
+            report(StageResult {
+                timed_out: budget::timed_out(),
+                ..StageResult::new(Stage::Part(*part), Some(count.to_string()), Duration::ZERO)
+            });
+        }
+    }
+
+    fn start(registry: Registry, limit: Option<Duration>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            budget::set_limit(limit);
+            serve(&registry, &listener);
+        });
+        address
+    }
+
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_answers_on_localhost() {
+        let day = Day::new(DayId::new(2022, 1), false, sum_lines);
+        let address = start(Registry::new(day), None);
+
+        let response = request(address, "POST", "/day/1/part/2", "1\n2\n3");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\"day\":1,\"part\":2,\"answer\":\"12\""));
+        assert!(response.contains("\"part_time_us\":"));
+
+        let response = request(address, "POST", "/day/1/part/1/explain", "1");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        let response = request(address, "POST", "/day/2/part/1", "1");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        let response = request(address, "GET", "/day/1/part/1", "");
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        let response = request(address, "POST", "/day/1/part/1", "1000\n\nx\n");
+        assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    }
+
+    #[test]
+    fn idle_client_does_not_block_others() {
+        let day = Day::new(DayId::new(2022, 1), false, sum_lines);
+        let address = start(Registry::new(day), None);
+
+        let _idle = TcpStream::connect(address).unwrap();
+        let response = request(address, "POST", "/day/1/part/1", "1\n2");
+        assert!(response.contains("\"answer\":\"3\""));
+    }
+
+    #[test]
+    fn time_budget_applies_to_requests() {
+        let day = Day::new(DayId::new(2022, 1), false, count_until_budget);
+        let address = start(Registry::new(day), Some(Duration::from_millis(20)));
+
+        let response = request(address, "POST", "/day/1/part/1", "");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\"timed_out\":true"), "{response}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forwards_other_days_to_their_binaries() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let binary = std::env::temp_dir().join(format!("serve-test-{}.sh", std::process::id()));
+        std::fs::write(
+            &binary,
+            "#!/bin/sh\n[ \"$*\" = 'serve - --time-budget 0.25' ] || exit 1\ncat > /dev/null\n\
+             printf 'HTTP/1.1 200 OK\\r\\nContent-Length: 9\\r\\n\\r\\n{\"day\":2}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut registry = Registry::new(Day::new(DayId::new(2022, 1), false, sum_lines));
+        registry.add_binary(2, binary.clone());
+        assert_eq!(registry.days(), [1, 2]);
+        let address = start(registry, Some(Duration::from_millis(250)));
+
+        let response = request(address, "POST", "/day/2/part/1", "A Y");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"day\":2}"));
+        let response = request(address, "POST", "/day/3/part/1", "1");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        std::fs::remove_file(binary).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+// only snapshot tests use this module
+#![allow(dead_code)]
+
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::PathBuf;
+
+use super::DayId;
+
+// set to `1` to (re)write snapshots from current output instead of comparing with them
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+// golden file of a rendering - `snapshots/<year>/<day>-<name>.txt`
+pub fn path(id: DayId, name: &str) -> PathBuf {
+    PathBuf::from(format!("snapshots/{}/{:02}-{name}.txt", id.year, id.day))
+}
+
+// line diff of expected and actual text, `-` lines only expected, `+` lines only actual
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    // longest common subsequence lengths of all the suffixes
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+// compares rendering with its golden file, panics with readable diff on mismatch
+pub fn assert_snapshot(id: DayId, name: &str, actual: &str) {
+    let path = path(id, name);
+    let actual = format!("{}\n", actual.trim_end());
+
+    if std::env::var(UPDATE_VAR).is_ok_and(|value| value == "1") {
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(&path, &actual).unwrap();
+        return;
+    }
+
+    let Ok(expected) = read_to_string(&path) else {
+        panic!(
+            "missing snapshot {}, run tests with {UPDATE_VAR}=1 to create it",
+            path.display()
+        );
+    };
+    assert!(
+        expected == actual,
+        "snapshot {} differs (- expected, + actual), run tests with {UPDATE_VAR}=1 to accept:\n{}",
+        path.display(),
+        diff(&expected, &actual)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_texts() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), "  a\n  b");
+        assert_eq!(diff("", ""), "");
+    }
+
+    #[test]
+    fn insertions() {
+        assert_eq!(diff("a\nc", "a\nb\nc\nd"), "  a\n+ b\n  c\n+ d");
+        assert_eq!(diff("", "a"), "+ a");
+    }
+
+    #[test]
+    fn deletions() {
+        assert_eq!(diff("a\nb\nc\nd", "b\nd"), "- a\n  b\n- c\n  d");
+        assert_eq!(diff("a", ""), "- a");
+    }
+
+    #[test]
+    fn changed_lines() {
+        assert_eq!(diff("a\nb\nc", "a\nx\nc"), "  a\n- b\n+ x\n  c");
+    }
+
+    #[test]
+    fn snapshot_paths() {
+        let path = path(DayId::new(2022, 7), "tree");
+        assert_eq!(path, PathBuf::from("snapshots/2022/07-tree.txt"));
+    }
+}
@@ -0,0 +1,301 @@
+// only days with step-through simulation use this module
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+use std::io::{self, BufRead, Write};
+
+// state of a step-by-step simulation, which can be examined between steps
+pub trait Inspect {
+    // named numeric values usable in `until` conditions, e.g. `("cycle", 20)`
+    fn values(&self) -> Vec<(&'static str, i64)>;
+
+    // human readable picture of current state
+    fn render(&self) -> String;
+}
+
+// named value for `Inspect::values`, saturating on overflow
+pub fn value(name: &'static str, value: impl TryInto<i64>) -> (&'static str, i64) {
+    (name, value.try_into().unwrap_or(i64::MAX))
+}
+
+const HELP: &str = "\
+commands:
+  s, step           perform single step (also on empty line)
+  n <count>         perform <count> steps
+  until <cond>      step until condition holds, e.g. `until cycle >= 20`
+                    (operators: == != < <= > >=)
+  p, print          print current state
+  v, values         print current values
+  h, help           print this help
+  q, quit           stop stepping";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "==" | "=" => Operator::Eq,
+            "!=" => Operator::Ne,
+            "<" => Operator::Lt,
+            "<=" => Operator::Le,
+            ">" => Operator::Gt,
+            ">=" => Operator::Ge,
+            _ => return None,
+        })
+    }
+
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Operator::Eq => left == right,
+            Operator::Ne => left != right,
+            Operator::Lt => left < right,
+            Operator::Le => left <= right,
+            Operator::Gt => left > right,
+            Operator::Ge => left >= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    name: String,
+    operator: Operator,
+    value: i64,
+}
+
+impl Condition {
+    fn parse(s: &str) -> Result<Self, String> {
+        let words = s.split_ascii_whitespace().collect::<Vec<_>>();
+        let [name, operator, value] = words[..] else {
+            return Err(format!("expected `<name> <operator> <value>`, got `{s}`"));
+        };
+        let operator =
+            Operator::parse(operator).ok_or_else(|| format!("unknown operator `{operator}`"))?;
+        let value = value
+            .parse()
+            .map_err(|_| format!("`{value}` is not a number"))?;
+        Ok(Self {
+            name: name.to_string(),
+            operator,
+            value,
+        })
+    }
+
+    fn holds(&self, values: &[(&'static str, i64)]) -> Result<bool, String> {
+        values
+            .iter()
+            .find(|(name, _)| *name == self.name)
+            .map(|(_, value)| self.operator.holds(*value, self.value))
+            .ok_or_else(|| format!("unknown value `{}`", self.name))
+    }
+}
+
+fn print_values(output: &mut impl Write, values: &[(&'static str, i64)]) -> io::Result<()> {
+    let line = values
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    writeln!(output, "{line}")
+}
+
+// interactive stepping through simulation on stdin/stdout
+pub fn prompt<S>(simulation: S)
+where
+    S: Iterator + Inspect,
+    S::Item: Debug,
+{
+    let stdin = io::stdin();
+    prompt_with(simulation, stdin.lock(), io::stdout()).unwrap();
+}
+
+pub fn prompt_with<S>(
+    mut simulation: S,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<usize>
+where
+    S: Iterator + Inspect,
+    S::Item: Debug,
+{
+    let mut steps = 0;
+    let mut finished = false;
+
+    writeln!(output, "{}", simulation.render().trim_end())?;
+    print_values(&mut output, &simulation.values())?;
+
+    let mut lines = input.lines();
+    loop {
+        write!(output, "step {steps}> ")?;
+        output.flush()?;
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let (command, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+
+        let (count, condition) = match command {
+            "" | "s" | "step" => (1, None),
+            "n" => {
+                if let Ok(count) = rest.trim().parse::<usize>() {
+                    (count, None)
+                } else {
+                    writeln!(output, "usage: n <count>")?;
+                    continue;
+                }
+            }
+            "until" => match Condition::parse(rest) {
+                Ok(condition) => (usize::MAX, Some(condition)),
+                Err(err) => {
+                    writeln!(output, "{err}")?;
+                    continue;
+                }
+            },
+            "p" | "print" => {
+                writeln!(output, "{}", simulation.render().trim_end())?;
+                continue;
+            }
+            "v" | "values" => {
+                print_values(&mut output, &simulation.values())?;
+                continue;
+            }
+            "h" | "help" => {
+                writeln!(output, "{HELP}")?;
+                continue;
+            }
+            "q" | "quit" => break,
+            _ => {
+                writeln!(output, "unknown command `{command}`, try `help`")?;
+                continue;
+            }
+        };
+
+        if finished {
+            writeln!(output, "simulation finished after {steps} steps")?;
+            continue;
+        }
+
+        for _ in 0..count {
+            if let Some(condition) = &condition {
+                match condition.holds(&simulation.values()) {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(err) => {
+                        writeln!(output, "{err}")?;
+                        break;
+                    }
+                }
+            }
+            if let Some(event) = simulation.next() {
+                steps += 1;
+                if count == 1 {
+                    writeln!(output, "{event:?}")?;
+                }
+            } else {
+                finished = true;
+                writeln!(output, "simulation finished after {steps} steps")?;
+                break;
+            }
+        }
+        print_values(&mut output, &simulation.values())?;
+    }
+
+    Ok(steps)
+}
+
+// runs the prompt on scripted commands, returns everything it printed
+pub fn run_script<S>(simulation: S, commands: &[&str]) -> String
+where
+    S: Iterator + Inspect,
+    S::Item: Debug,
+{
+    let mut output = vec![];
+    prompt_with(simulation, commands.join("\n").as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // counts up to the limit, one step at a time
+    struct Counter {
+        count: i64,
+        limit: i64,
+    }
+
+    impl Iterator for Counter {
+        type Item = i64;
+
+        fn next(&mut self) -> Option<i64> {
+            (self.count < self.limit).then(|| {
+                self.count += 1;
+                self.count
+            })
+        }
+    }
+
+    impl Inspect for Counter {
+        fn values(&self) -> Vec<(&'static str, i64)> {
+            vec![value("count", self.count), value("double", self.count * 2)]
+        }
+
+        fn render(&self) -> String {
+            "#".repeat(usize::try_from(self.count).unwrap())
+        }
+    }
+
+    fn counter(limit: i64) -> Counter {
+        Counter { count: 0, limit }
+    }
+
+    #[test]
+    fn steps_and_counts() {
+        let output = run_script(counter(10), &["", "s", "n 3", "p"]);
+        assert!(output.contains("step 1> 2\ncount=2, double=4"));
+        assert!(output.contains("count=5, double=10"));
+        assert!(output.ends_with("step 5> #####\nstep 5> "));
+    }
+
+    #[test]
+    fn until_condition() {
+        let output = run_script(counter(10), &["until double >= 8", "v"]);
+        assert!(output.ends_with("count=4, double=8\nstep 4> count=4, double=8\nstep 4> "));
+        let output = run_script(counter(10), &["until triple > 1", "until count ~ 1"]);
+        assert!(output.contains("unknown value `triple`"));
+        assert!(output.contains("unknown operator `~`"));
+        assert_eq!(
+            Condition::parse("count"),
+            Err("expected `<name> <operator> <value>`, got `count`".to_string())
+        );
+    }
+
+    #[test]
+    fn finished_simulation() {
+        let output = run_script(counter(2), &["n 5", "s", "q", "s"]);
+        assert_eq!(
+            output.matches("simulation finished after 2 steps").count(),
+            2
+        );
+        assert!(output.ends_with("step 2> "));
+        assert_eq!(
+            prompt_with(counter(2), "n 5\n".as_bytes(), io::sink()).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn usage_errors() {
+        let output = run_script(counter(2), &["n x", "jump"]);
+        assert!(output.contains("usage: n <count>"));
+        assert!(output.contains("unknown command `jump`, try `help`"));
+    }
+}
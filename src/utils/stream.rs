@@ -0,0 +1,109 @@
+// only days solvable incrementally use this module
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use super::runner::{Stage, StreamHook};
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+
+// buffered reader of input file, nothing is read until consumed
+pub fn open(path: &Path) -> io::Result<BufReader<File>> {
+    File::open(path).map(BufReader::new)
+}
+
+// drops byte order mark some editors start the file with,
+// read errors are left to be reported by the following reads
+fn skip_byte_order_mark<R: BufRead>(mut reader: R) -> R {
+    if reader
+        .fill_buf()
+        .is_ok_and(|buf| buf.starts_with(BYTE_ORDER_MARK))
+    {
+        reader.consume(BYTE_ORDER_MARK.len());
+    }
+    reader
+}
+
+// non-blank input lines one by one, without line endings and trailing whitespace
+// as in the normalized input read as a whole
+pub fn lines(reader: impl BufRead) -> impl Iterator<Item = io::Result<String>> {
+    skip_byte_order_mark(reader)
+        .lines()
+        .map(|line| line.map(|line| line.trim_end().to_string()))
+        .filter(|line| line.as_ref().map_or(true, |line| !line.is_empty()))
+}
+
+// input bytes one by one, after byte order mark
+pub fn bytes(reader: impl BufRead) -> impl Iterator<Item = io::Result<u8>> {
+    skip_byte_order_mark(reader).bytes()
+}
+
+// groups of lines separated by blank lines (e.g. calories of single elf on day 01),
+// lines without trailing whitespace, only a single group is held in memory at a time
+pub fn blocks<R: BufRead>(reader: R) -> Blocks<R> {
+    Blocks {
+        lines: skip_byte_order_mark(reader).lines(),
+    }
+}
+
+pub struct Blocks<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> Iterator for Blocks<R> {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = vec![];
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err)),
+            };
+            match line.trim_end() {
+                "" if block.is_empty() => {}
+                "" => return Some(Ok(block)),
+                line => block.push(line.to_string()),
+            }
+        }
+        (!block.is_empty()).then_some(Ok(block))
+    }
+}
+
+// answers reported by the stream hook reading the text
+pub fn answers(stream: StreamHook, data: &str) -> io::Result<Vec<String>> {
+    let mut answers = vec![];
+    stream(&mut data.as_bytes(), &mut |result| {
+        if let Stage::Part(_) = result.stage {
+            answers.extend(result.answer);
+        }
+    })?;
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_lines() {
+        let data = "\u{feff}a \r\n\r\n  b\t\n\n";
+        let lines = lines(data.as_bytes()).collect::<io::Result<Vec<_>>>();
+        assert_eq!(lines.unwrap(), ["a", "  b"]);
+    }
+
+    #[test]
+    fn blocks_of_lines() {
+        let data = "\u{feff}1\r\n2 \r\n\r\n \r\n\r\n3";
+        let blocks = blocks(data.as_bytes()).collect::<io::Result<Vec<_>>>();
+        assert_eq!(blocks.unwrap(), [vec!["1", "2"], vec!["3"]]);
+    }
+
+    #[test]
+    fn bytes_after_byte_order_mark() {
+        let bytes = bytes("\u{feff}ab".as_bytes()).collect::<io::Result<Vec<_>>>();
+        assert_eq!(bytes.unwrap(), b"ab");
+    }
+}
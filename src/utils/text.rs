@@ -0,0 +1,134 @@
+// only days parsing blank-line separated blocks use all of this module
+#![allow(dead_code)]
+
+use std::fmt::{Debug, Write};
+use std::mem;
+
+// LF line endings, no trailing whitespace, no blank lines at the start or the end
+// and a single blank line between blocks, without final newline as the stored inputs
+// and without byte order mark
+pub fn normalize(data: &str) -> String {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let mut normalized = String::with_capacity(data.len());
+    let mut blank = false;
+    for line in data.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank = !normalized.is_empty();
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push('\n');
+        }
+        if blank {
+            normalized.push('\n');
+            blank = false;
+        }
+        normalized.push_str(line);
+    }
+    normalized
+}
+
+// groups of lines separated by (any number of) blank lines, lines without trailing
+// whitespace and line endings of any kind
+pub fn blocks(data: &str) -> Vec<Vec<&str>> {
+    let mut blocks = vec![];
+    let mut block = vec![];
+    for line in data.lines().map(str::trim_end) {
+        if !line.is_empty() {
+            block.push(line);
+        } else if !block.is_empty() {
+            blocks.push(mem::take(&mut block));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+// the same input as saved by various editors and systems
+pub fn variants(data: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("byte order mark", format!("\u{feff}{data}")),
+        ("CRLF line endings", data.replace('\n', "\r\n")),
+        (
+            "trailing whitespace",
+            format!("{} \t ", data.replace('\n', " \t \n")),
+        ),
+        ("final newline", format!("{data}\n")),
+        ("trailing blank lines", format!("{data}\n\n\n")),
+        (
+            "extra blank lines",
+            format!("\n\n{}", data.replace("\n\n", "\n\n\n \n")),
+        ),
+    ]
+}
+
+// checks solution reading raw input (e.g. streamed) gives the same result
+// on all the variants of the input as on the input
+pub fn assert_same_on_variants<T: PartialEq + Debug>(data: &str, solve: impl Fn(&str) -> T) {
+    let expected = solve(data);
+    for (variant, variant_data) in variants(data) {
+        assert_eq!(solve(&variant_data), expected, "input with {variant}");
+    }
+}
+
+// text as JSON string literal, quotes and control characters escaped
+pub fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => write!(json, "\\u{:04x}", u32::from(ch)).unwrap(),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCKS: &str = "1000\n2000\n\n    [D]\n[N] [C]\n\nA Y";
+
+    #[test]
+    fn variants_normalize_to_stored_input() {
+        assert_eq!(normalize(BLOCKS), BLOCKS);
+        for (variant, data) in variants(BLOCKS) {
+            assert_eq!(normalize(&data), BLOCKS, "input with {variant}");
+        }
+    }
+
+    #[test]
+    fn leading_whitespace_is_kept() {
+        assert_eq!(normalize("\r\n \r\n  [D] \r\n"), "  [D]");
+    }
+
+    #[test]
+    fn blocks_of_variants() {
+        for (variant, data) in variants(BLOCKS) {
+            let data = normalize(&data);
+            assert_eq!(
+                blocks(&data),
+                [
+                    vec!["1000", "2000"],
+                    vec!["    [D]", "[N] [C]"],
+                    vec!["A Y"]
+                ],
+                "input with {variant}"
+            );
+        }
+    }
+
+    #[test]
+    fn json_escaping() {
+        assert_eq!(json_string("a \"b\"\\c"), r#""a \"b\"\\c""#);
+        assert_eq!(json_string("1\n2\t\u{1}"), r#""1\n2\t\u0001""#);
+    }
+}
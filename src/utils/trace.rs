@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// leveled debug output of solvers, printed to stderr so that answers stay clean
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    #[default]
+    Quiet = 0,
+    Verbose = 1,
+    Trace = 2,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Quiet as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+#[allow(dead_code)]
+pub fn enabled(level: Level) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+// intermediate results worth seeing on a normal debugging run
+#[macro_export]
+macro_rules! verbose {
+    ( $($arg:tt)* ) => {
+        if $crate::utils::trace::enabled($crate::utils::trace::Level::Verbose) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// step by step details, potentially huge amount of output
+#[macro_export]
+macro_rules! trace {
+    ( $($arg:tt)* ) => {
+        if $crate::utils::trace::enabled($crate::utils::trace::Level::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}